@@ -1,14 +1,8 @@
-use std::{
-    any::type_name,
-    fmt::{Debug, Display},
-};
-
 use ruvc::{
-    camera::*,
-    processing_unit::*,
+    control::{ControlRange, ControlValueDyn},
     topo::{
-        CameraControls, CameraId, CameraTerminalDesc, InputTerminalKind, ProcessingUnitControls,
-        ProcessingUnitDesc, SelectorUnitDesc, UnitKind,
+        CameraId, CameraTerminalDesc, ExtensionUnitDesc, InputTerminalKind, ProcessingUnitDesc,
+        SelectorUnitDesc, UnitKind,
     },
     UvcDevice, UvcDeviceDesc,
 };
@@ -53,6 +47,7 @@ fn list_device(desc: UvcDeviceDesc) -> ruvc::Result<()> {
         match unit.unit_kind() {
             UnitKind::Selector(desc) => list_selector_unit_controls(&dev, desc)?,
             UnitKind::Processing(desc) => list_processing_unit_controls(&dev, desc)?,
+            UnitKind::Extension(desc) => list_extension_unit_controls(&dev, desc)?,
             _ => {}
         }
     }
@@ -65,131 +60,89 @@ fn list_device(desc: UvcDeviceDesc) -> ruvc::Result<()> {
 fn list_camera_controls(
     dev: &UvcDevice,
     id: CameraId,
-    desc: &CameraTerminalDesc,
+    _desc: &CameraTerminalDesc,
 ) -> ruvc::Result<()> {
-    println!("Camera Terminal controls ({:?}):", id);
+    println!("Camera Terminal {} controls:", id);
 
     let cam = dev.camera_terminal_by_id(id);
-    let c = desc.controls();
-    if c.contains(CameraControls::SCANNING_MODE) {
-        print_cam_control::<ScanningMode>(&cam)?;
-    }
-    if c.contains(CameraControls::AUTO_EXPOSURE_MODE) {
-        print_cam_control::<AutoExposureMode>(&cam)?;
-    }
-    if c.contains(CameraControls::AUTO_EXPOSURE_PRIORITY) {
-        print_cam_control::<AutoExposurePriority>(&cam)?;
-    }
-    if c.contains(CameraControls::EXPOSURE_TIME_ABS) {
-        print_cam_control::<ExposureTimeAbs>(&cam)?;
-    }
-    if c.contains(CameraControls::EXPOSURE_TIME_REL) {
-        print_cam_control::<ExposureTimeRel>(&cam)?;
-    }
-    if c.contains(CameraControls::FOCUS_ABS) {
-        print_cam_control::<FocusAbs>(&cam)?;
-    }
-    if c.contains(CameraControls::FOCUS_REL) {
-        print_cam_control::<FocusRel>(&cam)?;
-    }
-    if c.contains(CameraControls::IRIS_ABS) {
-        print_cam_control::<IrisAbs>(&cam)?;
-    }
-    if c.contains(CameraControls::IRIS_REL) {
-        print_cam_control::<IrisRel>(&cam)?;
+    for control in cam.iter_controls() {
+        print_control(control);
     }
-    if c.contains(CameraControls::ZOOM_ABS) {
-        print_cam_control::<ZoomAbs>(&cam)?;
-    }
-    if c.contains(CameraControls::FOCUS_AUTO) {
-        print_cam_control::<FocusAuto>(&cam)?;
-    }
-    if c.contains(CameraControls::FOCUS_SIMPLE) {
-        print_cam_control::<FocusSimple>(&cam)?;
-    }
-    // TODO complete
-
-    Ok(())
-}
 
-fn print_cam_control<C: CameraControl>(cam: &CameraTerminal<'_>) -> ruvc::Result<()>
-where
-    C::Value: Debug,
-{
-    let name = type_name::<C>().split("::").last().unwrap();
-    println!(
-        "- {}: {:?} ({:?}-{:?}, step {:?}, default {:?})",
-        name,
-        cam.read_control::<C>()?,
-        cam.read_control_min::<C>()?,
-        cam.read_control_max::<C>()?,
-        cam.read_control_res::<C>()?,
-        cam.read_control_default::<C>()?,
-    );
     Ok(())
 }
 
 fn list_selector_unit_controls(dev: &UvcDevice, desc: &SelectorUnitDesc) -> ruvc::Result<()> {
-    eprintln!("NYI: selector unit controls");
+    println!("Selector Unit {}:", desc.id());
+    let selected = dev.selector_unit_by_id(desc.id()).get_selected_input()?;
+    for (i, input) in desc.inputs().iter().enumerate() {
+        let marker = if usize::from(selected) == i + 1 {
+            " (selected)"
+        } else {
+            ""
+        };
+        println!("- input pin {}: {}{}", i + 1, input, marker);
+    }
     Ok(())
 }
 
 fn list_processing_unit_controls(dev: &UvcDevice, desc: &ProcessingUnitDesc) -> ruvc::Result<()> {
-    println!("Processing Unit controls ({:?}):", desc.id());
+    println!("Processing Unit {} controls:", desc.id());
 
     let pu = dev.processing_unit_by_id(desc.id());
-    let c = desc.controls();
-    if c.contains(ProcessingUnitControls::BRIGHTNESS) {
-        print_pu_control::<Brightness>(&pu)?;
+    for control in pu.iter_controls() {
+        print_control(control);
     }
-    if c.contains(ProcessingUnitControls::CONTRAST) {
-        print_pu_control::<Contrast>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::HUE) {
-        print_pu_control::<Hue>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::SATURATION) {
-        print_pu_control::<Saturation>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::SHARPNESS) {
-        print_pu_control::<Sharpness>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::GAMMA) {
-        print_pu_control::<Gamma>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::WHITE_BALANCE_TEMPERATURE) {
-        print_pu_control::<WhiteBalanceTemperature>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::WHITE_BALANCE_COMPONENT) {
-        print_pu_control::<WhiteBalanceComponent>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::BACKLIGHT_COMPENSATION) {
-        print_pu_control::<BacklightCompensation>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::GAIN) {
-        print_pu_control::<Gain>(&pu)?;
-    }
-    if c.contains(ProcessingUnitControls::POWER_LINE_FREQUENCY) {
-        print_pu_control::<PowerLineFrequency>(&pu)?;
+
+    Ok(())
+}
+
+fn list_extension_unit_controls(dev: &UvcDevice, desc: &ExtensionUnitDesc) -> ruvc::Result<()> {
+    println!(
+        "Extension Unit {} ({}, {} controls):",
+        desc.id(),
+        desc.extension_code(),
+        desc.num_controls(),
+    );
+
+    // Bit `n` of `bmControls` corresponds to control selector `n + 1`.
+    let xu = dev.extension_unit_by_id(desc.id());
+    let bitmap = desc.raw_controls_bitmap();
+    for selector in (0..bitmap.len() * 8)
+        .filter(|bit| bitmap[bit / 8] & (1 << (bit % 8)) != 0)
+        .map(|bit| bit as u8 + 1)
+    {
+        // Vendor-specific controls frequently reject requests, so don't abort on errors.
+        match xu.read_control_len(selector) {
+            Ok(len) => println!("- selector {}: {} Bytes", selector, len),
+            Err(e) => println!("- selector {}: {}", selector, e),
+        }
     }
-    // TODO
 
     Ok(())
 }
 
-fn print_pu_control<C: ProcessingUnitControl>(pu: &ProcessingUnit<'_>) -> ruvc::Result<()>
-where
-    C::Value: Debug,
-{
-    let name = type_name::<C>().split("::").last().unwrap();
+fn print_control(control: ruvc::Result<(&str, ControlValueDyn, ControlRange)>) {
+    let (name, current, range) = match control {
+        Ok(control) => control,
+        Err(e) => {
+            println!("- {}", e);
+            return;
+        }
+    };
+
+    // Controls like Auto-Exposure Mode don't have a minimum, maximum, or resolution.
+    let show = |value: Option<&ControlValueDyn>| match value {
+        Some(value) => value.to_string(),
+        None => "-".to_string(),
+    };
     println!(
-        "- {}: {:?} ({:?}-{:?}, step {:?}, default {:?})",
+        "- {}: {} ({}-{}, step {}, default {})",
         name,
-        pu.read_control::<C>()?,
-        pu.read_control_min::<C>()?,
-        pu.read_control_max::<C>()?,
-        pu.read_control_res::<C>()?,
-        pu.read_control_default::<C>()?,
+        current,
+        show(range.min()),
+        show(range.max()),
+        show(range.res()),
+        show(range.default()),
     );
-    Ok(())
 }
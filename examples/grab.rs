@@ -0,0 +1,14 @@
+fn main() -> ruvc::Result<()> {
+    env_logger::init();
+
+    let (width, height, pixel_format, data) = ruvc::grab_one_frame()?;
+    println!(
+        "captured a {}x{} {:?} frame ({} Bytes)",
+        width,
+        height,
+        pixel_format,
+        data.len()
+    );
+
+    Ok(())
+}
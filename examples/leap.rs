@@ -5,7 +5,7 @@ use ruvc::{
     control::ProbeHint,
     processing_unit::*,
     streaming_interface::{Commit, Probe},
-    UvcDeviceDesc,
+    UvcDevice,
 };
 
 const LEAP_VID: u16 = 0xf182;
@@ -14,19 +14,16 @@ const LEAP_PID: u16 = 0x0003;
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init();
 
-    for desc in ruvc::list()? {
-        if desc.vendor_id() == LEAP_VID && desc.product_id() == LEAP_PID {
-            go(desc)?;
-            return Ok(());
+    match ruvc::open_by_vid_pid(LEAP_VID, LEAP_PID)? {
+        Some(dev) => go(dev),
+        None => {
+            eprintln!("no matching device found");
+            Ok(())
         }
     }
-
-    eprintln!("no matching device found");
-    Ok(())
 }
 
-fn go(desc: UvcDeviceDesc) -> Result<(), Box<dyn std::error::Error>> {
-    let dev = desc.open()?;
+fn go(dev: UvcDevice) -> Result<(), Box<dyn std::error::Error>> {
     println!("opened device '{}'", dev.read_product_string()?);
 
     let desc = match dev
@@ -87,20 +84,26 @@ fn go(desc: UvcDeviceDesc) -> Result<(), Box<dyn std::error::Error>> {
 
     println!("setup complete");
 
-    let interface = &dev.streaming_interfaces()[0];
+    let interface = match dev.default_streaming_interface() {
+        Some(interface) => interface,
+        None => {
+            eprintln!("device has no streaming interfaces");
+            return Ok(());
+        }
+    };
     let mut st = dev.streaming_interface_by_id(interface.id());
     let mut params = st.read_control_max::<Probe>()?;
-    log::trace!("GET_MAX(PROBE) = {:?}", params);
+    log::trace!("GET_MAX(PROBE) = {}", params);
 
     params.bmHint = params.bmHint | ProbeHint::FIX_FRAME_INTERVAL;
     params.dwFrameInterval = 86956;
-    log::trace!("SET_CUR(PROBE) = {:?}", params);
+    log::trace!("SET_CUR(PROBE) = {}", params);
     st.set_control::<Probe>(params)?;
 
     params = st.read_control::<Probe>()?;
-    log::trace!("GET_CUR(PROBE) = {:?}", params);
+    log::trace!("GET_CUR(PROBE) = {}", params);
     st.set_control::<Commit>(params)?;
-    let mut stream = st.start_stream_no_negotiate();
+    let mut stream = st.start_stream_no_negotiate()?;
 
     println!("stream started");
 
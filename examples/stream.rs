@@ -11,7 +11,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    let interface = &dev.streaming_interfaces()[0];
+    let interface = match dev.default_streaming_interface() {
+        Some(interface) => interface,
+        None => {
+            eprintln!("device has no streaming interfaces");
+            return Ok(());
+        }
+    };
 
     let interface_id = interface.id();
     let format = interface.formats()[0].index();
@@ -19,7 +25,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut interface = dev.streaming_interface_by_id(interface_id);
     let mut stream = interface.start_stream(format, frame)?;
 
-    println!("stream started");
+    if let (Some((width, height)), Some(interval)) = (stream.resolution(), stream.frame_interval())
+    {
+        let fps = 1.0 / interval.as_secs_f64();
+        println!("stream started: {}x{} @ {:.2} fps", width, height, fps);
+    } else {
+        println!("stream started");
+    }
 
     let mut buf = vec![0; 1024];
     loop {
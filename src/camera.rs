@@ -1,7 +1,11 @@
 use crate::{
-    control::ControlValue,
-    topo::{CameraId, CameraTerminalDesc},
-    Request, Result, UvcDevice,
+    control::{
+        ControlDescriptor, ControlInfo, ControlRange, ControlValue, ControlValueDyn, FocusAutoMode,
+    },
+    error::{err, Action, ControlAddress},
+    request::Request,
+    topo::{CameraControls, CameraId, CameraTerminalDesc},
+    Result, UvcDevice,
 };
 
 /// Grants access to a camera input terminal.
@@ -19,6 +23,113 @@ impl<'a> CameraTerminal<'a> {
         Self { device, id, desc }
     }
 
+    /// Returns the controls supported by this camera terminal.
+    ///
+    /// This is derived from the `bmControls` bitmap in the terminal's descriptor.
+    pub fn supported_controls(&self) -> Vec<ControlId> {
+        let controls = self.desc.controls();
+        CONTROL_BITS
+            .iter()
+            .filter(|(bit, _)| controls.contains(*bit))
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
+    /// Returns the descriptors of the controls supported by this camera terminal.
+    ///
+    /// Together with the `*_dyn` methods, this allows accessing controls whose selectors are only
+    /// known at runtime.
+    pub fn control_descriptors(&self) -> Vec<ControlDescriptor<ControlId>> {
+        self.supported_controls()
+            .into_iter()
+            .map(ControlId::descriptor)
+            .collect()
+    }
+
+    /// Returns an iterator that reads the current value and range of every supported control.
+    ///
+    /// Each item contains the control's name, its current value, and its range. An item is only
+    /// an error if reading the current value fails. Range values the device rejects are left out
+    /// of the [`ControlRange`], since many controls don't support all of them.
+    pub fn iter_controls(
+        &self,
+    ) -> impl Iterator<Item = Result<(&'static str, ControlValueDyn, ControlRange)>> + '_ {
+        self.supported_controls().into_iter().map(move |id| {
+            let current = id.decode_dyn(self.read_control_dyn(id)?);
+            let read = |req| match self.read_control_vec(id, req) {
+                Ok(value) => Some(id.decode_dyn(value)),
+                Err(e) => {
+                    log::debug!("{:?} of {} failed: {}", req, id.name(), e);
+                    None
+                }
+            };
+            let range = ControlRange::new(
+                read(Request::GetMin),
+                read(Request::GetMax),
+                read(Request::GetRes),
+                read(Request::GetDef),
+            );
+            Ok((id.name(), current, range))
+        })
+    }
+
+    /// Reads the current value of the control `id` without decoding it.
+    pub fn read_control_dyn(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetCur)
+    }
+
+    /// Reads the minimum value of the control `id` without decoding it.
+    pub fn read_control_dyn_min(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetMin)
+    }
+
+    /// Reads the maximum value of the control `id` without decoding it.
+    pub fn read_control_dyn_max(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetMax)
+    }
+
+    /// Reads the resolution of the control `id` without decoding it.
+    pub fn read_control_dyn_res(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetRes)
+    }
+
+    /// Reads the default value of the control `id` without decoding it.
+    pub fn read_control_dyn_default(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetDef)
+    }
+
+    /// Sets the control `id` to the raw `value`.
+    ///
+    /// `value` should be [`ControlId::value_len`] Bytes long.
+    pub fn set_control_dyn(&mut self, id: ControlId, value: &[u8]) -> Result<()> {
+        self.set_control_raw(id, value)
+    }
+
+    fn read_control_vec(&self, id: ControlId, req: Request) -> Result<Vec<u8>> {
+        let mut buf = vec![0; id.value_len()];
+        self.read_control_raw(id, req, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reads the current value of the control `C` without decoding it.
+    ///
+    /// This is useful for telling apart decoding bugs from devices returning unexpected values.
+    pub fn read_control_raw_vec<C: CameraControl>(&self) -> Result<Vec<u8>> {
+        self.read_control_vec(C::ID, Request::GetCur)
+    }
+
+    /// Reads the capabilities and state of the control `C` with a `GET_INFO` request.
+    pub fn read_control_info<C: CameraControl>(&self) -> Result<ControlInfo> {
+        let mut buf = [0; 1];
+        self.read_control_raw(C::ID, Request::GetInfo, &mut buf)?;
+        Ok(ControlInfo::from_bits_truncate(buf[0]))
+    }
+
+    /// Queries the length of the control `C` in Bytes with a `GET_LEN` request.
+    pub fn read_control_len<C: CameraControl>(&self) -> Result<u16> {
+        self.device.read_control_len(self.id.as_raw(), C::ID as _)
+    }
+
     pub fn read_control<C: CameraControl>(&self) -> Result<C::Value> {
         let mut buf = <<C::Value as ControlValue>::Buf>::default();
         self.read_control_raw(C::ID, Request::GetCur, buf.as_mut())?;
@@ -55,18 +166,77 @@ impl<'a> CameraTerminal<'a> {
         self.set_control_raw(C::ID, buf.as_mut())
     }
 
+    /// Resets a control to its default value.
+    ///
+    /// This reads the default value with `GET_DEF` and writes it back with `SET_CUR`, without
+    /// decoding it.
+    pub fn reset_to_default<C: CameraControl>(&mut self) -> Result<()> {
+        let mut buf = <<C::Value as ControlValue>::Buf>::default();
+        self.read_control_raw(C::ID, Request::GetDef, buf.as_mut())?;
+        self.set_control_raw(C::ID, buf.as_mut())
+    }
+
+    /// Reads the Focus, Auto Control as a [`FocusAutoMode`].
+    ///
+    /// Returns `None` if the device reports a `GET_MAX` below 2 for the control, in which case it
+    /// only supports the two states of [`FocusAuto`]. Returns an error if the device's current
+    /// value is not a valid [`FocusAutoMode`].
+    pub fn read_focus_auto_mode(&self) -> Result<Option<FocusAutoMode>> {
+        if !self.has_focus_auto_modes()? {
+            return Ok(None);
+        }
+
+        let mut buf = [0; 1];
+        self.read_control_raw(ControlId::FocusAuto, Request::GetCur, &mut buf)?;
+        match FocusAutoMode::from_raw(buf[0]) {
+            Some(mode) => Ok(Some(mode)),
+            None => err(
+                format!("invalid Focus, Auto value {}", buf[0]),
+                Action::ReadingControl(self.focus_auto_address()),
+            ),
+        }
+    }
+
+    /// Sets the Focus, Auto Control to `mode`.
+    ///
+    /// [`FocusAutoMode::Continuous`] is only accepted if the device reports a `GET_MAX` of at
+    /// least 2 for the control, otherwise an error is returned.
+    pub fn set_focus_auto_mode(&mut self, mode: FocusAutoMode) -> Result<()> {
+        if mode == FocusAutoMode::Continuous && !self.has_focus_auto_modes()? {
+            return err(
+                "device does not support continuous autofocus",
+                Action::WritingControl(self.focus_auto_address()),
+            );
+        }
+
+        self.set_control_raw(ControlId::FocusAuto, &[mode as u8])
+    }
+
+    /// Returns whether the device's `GET_MAX` of the Focus, Auto Control indicates support for
+    /// [`FocusAutoMode::Continuous`].
+    fn has_focus_auto_modes(&self) -> Result<bool> {
+        let mut buf = [0; 1];
+        self.read_control_raw(ControlId::FocusAuto, Request::GetMax, &mut buf)?;
+        Ok(buf[0] >= FocusAutoMode::Continuous as u8)
+    }
+
+    fn focus_auto_address(&self) -> ControlAddress {
+        self.device
+            .entity_control_address(self.id.as_raw(), ControlId::FocusAuto as _)
+    }
+
     fn set_control_raw(&mut self, control: ControlId, value: &[u8]) -> Result<()> {
         self.device
             .set_entity(self.id.as_raw(), control as _, value)
     }
 
-    fn read_control_raw(&self, control: ControlId, req: Request, buf: &mut [u8]) -> Result<()> {
+    fn read_control_raw(&self, control: ControlId, req: Request, buf: &mut [u8]) -> Result<usize> {
         self.device
             .read_entity(self.id.as_raw(), req, control as _, buf)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ControlId {
     Undefined = 0x00,
@@ -92,6 +262,128 @@ pub enum ControlId {
     RegionOfInterest = 0x14,
 }
 
+impl ControlId {
+    /// Returns the length of the control's value in Bytes, as specified by UVC.
+    ///
+    /// Returns 0 for [`ControlId::Undefined`].
+    pub fn value_len(self) -> usize {
+        match self {
+            ControlId::Undefined => 0,
+            ControlId::ScanningMode => 1,
+            ControlId::AutoExposureMode => 1,
+            ControlId::AutoExposurePriority => 1,
+            ControlId::ExposureTimeAbs => 4,
+            ControlId::ExposureTimeRel => 1,
+            ControlId::FocusAbs => 2,
+            ControlId::FocusRel => 2,
+            ControlId::FocusAuto => 1,
+            ControlId::IrisAbs => 2,
+            ControlId::IrisRel => 1,
+            ControlId::ZoomAbs => 2,
+            ControlId::ZoomRel => 3,
+            ControlId::PanTiltAbs => 8,
+            ControlId::PanTiltRel => 4,
+            ControlId::RollAbs => 2,
+            ControlId::RollRel => 2,
+            ControlId::Privacy => 1,
+            ControlId::FocusSimple => 1,
+            ControlId::Window => 12,
+            ControlId::RegionOfInterest => 10,
+        }
+    }
+
+    /// Returns the control's name, as used in the UVC specification.
+    pub fn name(self) -> &'static str {
+        match self {
+            ControlId::Undefined => "Undefined",
+            ControlId::ScanningMode => "Scanning Mode",
+            ControlId::AutoExposureMode => "Auto-Exposure Mode",
+            ControlId::AutoExposurePriority => "Auto-Exposure Priority",
+            ControlId::ExposureTimeAbs => "Exposure Time (Absolute)",
+            ControlId::ExposureTimeRel => "Exposure Time (Relative)",
+            ControlId::FocusAbs => "Focus (Absolute)",
+            ControlId::FocusRel => "Focus (Relative)",
+            ControlId::FocusAuto => "Focus, Auto",
+            ControlId::IrisAbs => "Iris (Absolute)",
+            ControlId::IrisRel => "Iris (Relative)",
+            ControlId::ZoomAbs => "Zoom (Absolute)",
+            ControlId::ZoomRel => "Zoom (Relative)",
+            ControlId::PanTiltAbs => "PanTilt (Absolute)",
+            ControlId::PanTiltRel => "PanTilt (Relative)",
+            ControlId::RollAbs => "Roll (Absolute)",
+            ControlId::RollRel => "Roll (Relative)",
+            ControlId::Privacy => "Privacy",
+            ControlId::FocusSimple => "Focus, Simple Range",
+            ControlId::Window => "Digital Window",
+            ControlId::RegionOfInterest => "Digital Region of Interest",
+        }
+    }
+
+    /// Decodes a raw value of this control into the matching [`ControlValueDyn`] variant.
+    fn decode_dyn(self, raw: Vec<u8>) -> ControlValueDyn {
+        match self {
+            ControlId::ScanningMode | ControlId::AutoExposurePriority | ControlId::Privacy => {
+                ControlValueDyn::Bool(bool::decode(&raw))
+            }
+            ControlId::AutoExposureMode
+            | ControlId::FocusAuto
+            | ControlId::IrisRel
+            | ControlId::FocusSimple => ControlValueDyn::U8(u8::decode(&raw)),
+            ControlId::ExposureTimeRel => ControlValueDyn::I8(i8::decode(&raw)),
+            ControlId::ExposureTimeAbs => ControlValueDyn::U32(u32::decode(&raw)),
+            ControlId::FocusAbs | ControlId::IrisAbs | ControlId::ZoomAbs => {
+                ControlValueDyn::U16(u16::decode(&raw))
+            }
+            ControlId::RollAbs => ControlValueDyn::I16(i16::decode(&raw)),
+            _ => ControlValueDyn::Raw(raw),
+        }
+    }
+
+    /// Returns the static information about this control.
+    pub fn descriptor(self) -> ControlDescriptor<ControlId> {
+        ControlDescriptor::new(self, self.value_len(), self.name())
+    }
+}
+
+/// Maps the bits of the camera terminal's `bmControls` to the corresponding control selectors.
+const CONTROL_BITS: &[(CameraControls, ControlId)] = &[
+    (CameraControls::SCANNING_MODE, ControlId::ScanningMode),
+    (
+        CameraControls::AUTO_EXPOSURE_MODE,
+        ControlId::AutoExposureMode,
+    ),
+    (
+        CameraControls::AUTO_EXPOSURE_PRIORITY,
+        ControlId::AutoExposurePriority,
+    ),
+    (
+        CameraControls::EXPOSURE_TIME_ABS,
+        ControlId::ExposureTimeAbs,
+    ),
+    (
+        CameraControls::EXPOSURE_TIME_REL,
+        ControlId::ExposureTimeRel,
+    ),
+    (CameraControls::FOCUS_ABS, ControlId::FocusAbs),
+    (CameraControls::FOCUS_REL, ControlId::FocusRel),
+    (CameraControls::IRIS_ABS, ControlId::IrisAbs),
+    (CameraControls::IRIS_REL, ControlId::IrisRel),
+    (CameraControls::ZOOM_ABS, ControlId::ZoomAbs),
+    (CameraControls::ZOOM_REL, ControlId::ZoomRel),
+    (CameraControls::PAN_TILT_ABS, ControlId::PanTiltAbs),
+    (CameraControls::PAN_TILT_REL, ControlId::PanTiltRel),
+    (CameraControls::ROLL_ABS, ControlId::RollAbs),
+    (CameraControls::ROLL_REL, ControlId::RollRel),
+    (CameraControls::FOCUS_AUTO, ControlId::FocusAuto),
+    (CameraControls::PRIVACY, ControlId::Privacy),
+    (CameraControls::FOCUS_SIMPLE, ControlId::FocusSimple),
+    (CameraControls::WINDOW, ControlId::Window),
+    (
+        CameraControls::REGION_OF_INTEREST,
+        ControlId::RegionOfInterest,
+    ),
+];
+
 pub trait CameraControl {
     // TODO seal
     type Value: ControlValue;
@@ -110,21 +402,25 @@ impl CameraControl for AutoExposureMode {
     const ID: ControlId = ControlId::AutoExposureMode;
 }
 
+/// Whether the device may vary the frame rate to achieve the desired exposure.
+///
+/// `false` keeps the frame rate constant, `true` allows the device to lower it dynamically when
+/// an automatic exposure mode is active.
 pub struct AutoExposurePriority;
 impl CameraControl for AutoExposurePriority {
-    type Value = u8;
+    type Value = bool;
     const ID: ControlId = ControlId::AutoExposurePriority;
 }
 
 pub struct ExposureTimeAbs;
 impl CameraControl for ExposureTimeAbs {
-    type Value = u32;
+    type Value = crate::control::ExposureTimeAbs;
     const ID: ControlId = ControlId::ExposureTimeAbs;
 }
 
 pub struct ExposureTimeRel;
 impl CameraControl for ExposureTimeRel {
-    type Value = i8;
+    type Value = crate::control::ExposureChange;
     const ID: ControlId = ControlId::ExposureTimeRel;
 }
 
@@ -169,3 +465,53 @@ impl CameraControl for ZoomAbs {
     type Value = u16;
     const ID: ControlId = ControlId::ZoomAbs;
 }
+
+pub struct ZoomRel;
+impl CameraControl for ZoomRel {
+    type Value = crate::control::ZoomRel;
+    const ID: ControlId = ControlId::ZoomRel;
+}
+
+pub struct PanTiltAbs;
+impl CameraControl for PanTiltAbs {
+    type Value = crate::control::PanTiltAbs;
+    const ID: ControlId = ControlId::PanTiltAbs;
+}
+
+pub struct PanTiltRel;
+impl CameraControl for PanTiltRel {
+    type Value = crate::control::PanTiltRel;
+    const ID: ControlId = ControlId::PanTiltRel;
+}
+
+/// The camera's rotation around its optical axis in degrees.
+pub struct RollAbs;
+impl CameraControl for RollAbs {
+    type Value = i16;
+    const ID: ControlId = ControlId::RollAbs;
+}
+
+pub struct RollRel;
+impl CameraControl for RollRel {
+    type Value = crate::control::RollRel;
+    const ID: ControlId = ControlId::RollRel;
+}
+
+/// Whether the camera's privacy shutter is closed (`true`) or open (`false`).
+pub struct Privacy;
+impl CameraControl for Privacy {
+    type Value = bool;
+    const ID: ControlId = ControlId::Privacy;
+}
+
+pub struct Window;
+impl CameraControl for Window {
+    type Value = crate::control::DigitalWindow;
+    const ID: ControlId = ControlId::Window;
+}
+
+pub struct RegionOfInterest;
+impl CameraControl for RegionOfInterest {
+    type Value = crate::control::RegionOfInterest;
+    const ID: ControlId = ControlId::RegionOfInterest;
+}
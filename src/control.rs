@@ -3,6 +3,8 @@ use std::{fmt, time::Duration};
 use bitflags::bitflags;
 use zerocopy::{AsBytes, FromBytes};
 
+use crate::BcdVersion;
+
 /// Raw value of entity controls.
 
 pub trait ControlValue {
@@ -10,6 +12,126 @@ pub trait ControlValue {
 
     fn decode(buf: &[u8]) -> Self;
     fn encode(&self, buf: &mut [u8]);
+
+    /// Decodes a value, returning `None` if `buf` does not contain a valid value.
+    ///
+    /// Unlike [`ControlValue::decode`], this does not substitute a fallback value when the device
+    /// returns an invalid one.
+    fn try_decode(buf: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Some(Self::decode(buf))
+    }
+}
+
+/// Static information about a control, for use with the dynamic (non-generic) control API.
+///
+/// `I` is the control selector type of the entity the control belongs to (eg.
+/// [`camera::ControlId`][crate::camera::ControlId]).
+#[derive(Debug, Clone, Copy)]
+pub struct ControlDescriptor<I> {
+    id: I,
+    value_len: usize,
+    name: &'static str,
+}
+
+impl<I: Copy> ControlDescriptor<I> {
+    pub(crate) fn new(id: I, value_len: usize, name: &'static str) -> Self {
+        Self {
+            id,
+            value_len,
+            name,
+        }
+    }
+
+    pub fn id(&self) -> I {
+        self.id
+    }
+
+    /// Returns the length of the control's value in Bytes.
+    pub fn value_len(&self) -> usize {
+        self.value_len
+    }
+
+    /// Returns the control's name, as used in the UVC specification.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// A control value whose type is only known at runtime.
+///
+/// Values with a structure that has no dedicated variant are returned as their raw bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ControlValueDyn {
+    Bool(bool),
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    Raw(Vec<u8>),
+}
+
+impl fmt::Display for ControlValueDyn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlValueDyn::Bool(v) => v.fmt(f),
+            ControlValueDyn::U8(v) => v.fmt(f),
+            ControlValueDyn::I8(v) => v.fmt(f),
+            ControlValueDyn::U16(v) => v.fmt(f),
+            ControlValueDyn::I16(v) => v.fmt(f),
+            ControlValueDyn::U32(v) => v.fmt(f),
+            ControlValueDyn::Raw(bytes) => write!(f, "{:02x?}", bytes),
+        }
+    }
+}
+
+/// The `GET_MIN`, `GET_MAX`, `GET_RES`, and `GET_DEF` values of a control.
+///
+/// Each value is `None` if the device rejected the corresponding request. Not every control
+/// supports all of them: the UVC specification doesn't define a minimum, maximum, or resolution
+/// for controls like Auto-Exposure Mode or Privacy.
+#[derive(Debug, Clone)]
+pub struct ControlRange {
+    min: Option<ControlValueDyn>,
+    max: Option<ControlValueDyn>,
+    res: Option<ControlValueDyn>,
+    default: Option<ControlValueDyn>,
+}
+
+impl ControlRange {
+    pub(crate) fn new(
+        min: Option<ControlValueDyn>,
+        max: Option<ControlValueDyn>,
+        res: Option<ControlValueDyn>,
+        default: Option<ControlValueDyn>,
+    ) -> Self {
+        Self {
+            min,
+            max,
+            res,
+            default,
+        }
+    }
+
+    pub fn min(&self) -> Option<&ControlValueDyn> {
+        self.min.as_ref()
+    }
+
+    pub fn max(&self) -> Option<&ControlValueDyn> {
+        self.max.as_ref()
+    }
+
+    pub fn res(&self) -> Option<&ControlValueDyn> {
+        self.res.as_ref()
+    }
+
+    pub fn default(&self) -> Option<&ControlValueDyn> {
+        self.default.as_ref()
+    }
 }
 
 impl ControlValue for bool {
@@ -97,28 +219,27 @@ impl ControlValue for u32 {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub enum PowerLineFrequency {
-    Disabled = 0,
-    Freq50Hz = 1,
-    Freq60Hz = 2,
-    Auto = 3,
+primitive_enum! {
+    pub enum PowerLineFrequency: u8 {
+        Disabled = 0,
+        Freq50Hz = 1,
+        Freq60Hz = 2,
+        Auto = 3,
+    }
 }
 
 impl ControlValue for PowerLineFrequency {
     type Buf = [u8; 1];
 
     fn decode(buf: &[u8]) -> Self {
-        match buf[0] {
-            0 => Self::Disabled,
-            1 => Self::Freq50Hz,
-            2 => Self::Freq60Hz,
-            3 => Self::Auto,
-            n => {
-                log::warn!("invalid power line frequency value {}", n);
-                Self::Disabled
-            }
-        }
+        Self::try_decode(buf).unwrap_or_else(|| {
+            log::warn!("invalid power line frequency value {}", buf[0]);
+            Self::Disabled
+        })
+    }
+
+    fn try_decode(buf: &[u8]) -> Option<Self> {
+        Self::from_raw(buf[0])
     }
 
     fn encode(&self, buf: &mut [u8]) {
@@ -136,6 +257,14 @@ impl WhiteBalanceComponents {
     pub fn new(blue: u16, red: u16) -> Self {
         Self { blue, red }
     }
+
+    pub fn blue(&self) -> u16 {
+        self.blue
+    }
+
+    pub fn red(&self) -> u16 {
+        self.red
+    }
 }
 
 impl ControlValue for WhiteBalanceComponents {
@@ -216,6 +345,38 @@ impl ControlValue for ExposureTimeAbs {
     }
 }
 
+primitive_enum! {
+    /// Value of the Exposure Time (Relative) Control.
+    pub enum ExposureChange: u8 {
+        /// Reset the exposure time to its default value (the exact effect is
+        /// implementation-specific).
+        SetDefault = 0x00,
+        /// Increment the exposure time by one step.
+        Increment = 0x01,
+        /// Decrement the exposure time by one step.
+        Decrement = 0xFF,
+    }
+}
+
+impl ControlValue for ExposureChange {
+    type Buf = [u8; 1];
+
+    fn decode(buf: &[u8]) -> Self {
+        Self::try_decode(buf).unwrap_or_else(|| {
+            log::warn!("invalid relative exposure time value {}", buf[0]);
+            Self::SetDefault
+        })
+    }
+
+    fn try_decode(buf: &[u8]) -> Option<Self> {
+        Self::from_raw(buf[0])
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        buf[0] = *self as u8;
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct FocusRel {
     focus_rel: i8,
@@ -226,6 +387,16 @@ impl FocusRel {
     pub fn new(focus_rel: i8, speed: u8) -> Self {
         Self { focus_rel, speed }
     }
+
+    /// Returns the direction of focus movement: 1 moves the focus nearer, -1 moves it towards
+    /// infinity, and 0 stops the movement.
+    pub fn focus_rel(&self) -> i8 {
+        self.focus_rel
+    }
+
+    pub fn speed(&self) -> u8 {
+        self.speed
+    }
 }
 
 impl ControlValue for FocusRel {
@@ -244,6 +415,341 @@ impl ControlValue for FocusRel {
     }
 }
 
+/// Value of the Zoom (Relative) Control.
+#[derive(Debug, Clone, Copy)]
+pub struct ZoomRel {
+    zoom: i8,
+    digital_zoom: bool,
+    speed: u8,
+}
+
+impl ZoomRel {
+    pub fn new(zoom: i8, digital_zoom: bool, speed: u8) -> Self {
+        Self {
+            zoom,
+            digital_zoom,
+            speed,
+        }
+    }
+
+    /// Returns the direction of zoom movement: 1 zooms in (towards telephoto), -1 zooms out
+    /// (towards wide-angle), and 0 stops the movement.
+    pub fn zoom(&self) -> i8 {
+        self.zoom
+    }
+
+    /// Returns whether digital zoom is enabled.
+    pub fn digital_zoom(&self) -> bool {
+        self.digital_zoom
+    }
+
+    pub fn speed(&self) -> u8 {
+        self.speed
+    }
+}
+
+impl ControlValue for ZoomRel {
+    type Buf = [u8; 3];
+
+    fn decode(buf: &[u8]) -> Self {
+        Self {
+            zoom: buf[0] as i8,
+            digital_zoom: buf[1] != 0,
+            speed: buf[2],
+        }
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        buf[0] = self.zoom as u8;
+        buf[1] = self.digital_zoom as u8;
+        buf[2] = self.speed;
+    }
+}
+
+/// Value of the PanTilt (Absolute) Control.
+#[derive(Debug, Clone, Copy)]
+pub struct PanTiltAbs {
+    pan: i32,
+    tilt: i32,
+}
+
+impl PanTiltAbs {
+    pub fn new(pan: i32, tilt: i32) -> Self {
+        Self { pan, tilt }
+    }
+
+    /// Returns the pan angle in arc seconds. Positive values rotate clockwise (as seen from
+    /// above).
+    pub fn pan(&self) -> i32 {
+        self.pan
+    }
+
+    /// Returns the tilt angle in arc seconds. Positive values point the camera up.
+    pub fn tilt(&self) -> i32 {
+        self.tilt
+    }
+}
+
+impl ControlValue for PanTiltAbs {
+    type Buf = [u8; 8];
+
+    fn decode(buf: &[u8]) -> Self {
+        let mut pan = [0; 4];
+        let mut tilt = [0; 4];
+        pan.copy_from_slice(&buf[0..4]);
+        tilt.copy_from_slice(&buf[4..8]);
+        Self {
+            pan: i32::from_le_bytes(pan),
+            tilt: i32::from_le_bytes(tilt),
+        }
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        buf[0..4].copy_from_slice(&self.pan.to_le_bytes());
+        buf[4..8].copy_from_slice(&self.tilt.to_le_bytes());
+    }
+}
+
+/// Value of the PanTilt (Relative) Control.
+#[derive(Debug, Clone, Copy)]
+pub struct PanTiltRel {
+    pan_rel: i8,
+    pan_speed: u8,
+    tilt_rel: i8,
+    tilt_speed: u8,
+}
+
+impl PanTiltRel {
+    pub fn new(pan_rel: i8, pan_speed: u8, tilt_rel: i8, tilt_speed: u8) -> Self {
+        Self {
+            pan_rel,
+            pan_speed,
+            tilt_rel,
+            tilt_speed,
+        }
+    }
+
+    /// Returns the direction of pan movement: 1 rotates clockwise, -1 counterclockwise, and 0
+    /// stops the movement.
+    pub fn pan_rel(&self) -> i8 {
+        self.pan_rel
+    }
+
+    pub fn pan_speed(&self) -> u8 {
+        self.pan_speed
+    }
+
+    /// Returns the direction of tilt movement: 1 points the camera up, -1 down, and 0 stops the
+    /// movement.
+    pub fn tilt_rel(&self) -> i8 {
+        self.tilt_rel
+    }
+
+    pub fn tilt_speed(&self) -> u8 {
+        self.tilt_speed
+    }
+}
+
+impl ControlValue for PanTiltRel {
+    type Buf = [u8; 4];
+
+    fn decode(buf: &[u8]) -> Self {
+        Self {
+            pan_rel: buf[0] as i8,
+            pan_speed: buf[1],
+            tilt_rel: buf[2] as i8,
+            tilt_speed: buf[3],
+        }
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        buf[0] = self.pan_rel as u8;
+        buf[1] = self.pan_speed;
+        buf[2] = self.tilt_rel as u8;
+        buf[3] = self.tilt_speed;
+    }
+}
+
+/// Value of the Roll (Relative) Control.
+#[derive(Debug, Clone, Copy)]
+pub struct RollRel {
+    roll_rel: i8,
+    speed: u8,
+}
+
+impl RollRel {
+    pub fn new(roll_rel: i8, speed: u8) -> Self {
+        Self { roll_rel, speed }
+    }
+
+    /// Returns the direction of roll movement: 1 rotates clockwise, -1 counterclockwise, and 0
+    /// stops the movement.
+    pub fn roll_rel(&self) -> i8 {
+        self.roll_rel
+    }
+
+    pub fn speed(&self) -> u8 {
+        self.speed
+    }
+}
+
+impl ControlValue for RollRel {
+    type Buf = [u8; 2];
+
+    fn decode(buf: &[u8]) -> Self {
+        Self {
+            roll_rel: buf[0] as i8,
+            speed: buf[1],
+        }
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        buf[0] = self.roll_rel as u8;
+        buf[1] = self.speed;
+    }
+}
+
+/// A rectangle in pixel coordinates of the sensor, as used by the Digital Window and Digital
+/// Region of Interest Controls.
+#[derive(Debug, Clone, Copy)]
+pub struct Rect {
+    pub top: u16,
+    pub left: u16,
+    pub bottom: u16,
+    pub right: u16,
+}
+
+impl Rect {
+    fn decode(buf: &[u8]) -> Self {
+        let field = |i: usize| u16::from_le_bytes([buf[i * 2], buf[i * 2 + 1]]);
+        Self {
+            top: field(0),
+            left: field(1),
+            bottom: field(2),
+            right: field(3),
+        }
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        for (i, value) in [self.top, self.left, self.bottom, self.right]
+            .iter()
+            .enumerate()
+        {
+            buf[i * 2..i * 2 + 2].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+/// Value of the Digital Window Control (UVC 1.5).
+#[derive(Debug, Clone, Copy)]
+pub struct DigitalWindow {
+    window: Rect,
+    num_steps: u16,
+    num_steps_units: u16,
+}
+
+impl DigitalWindow {
+    pub fn new(window: Rect, num_steps: u16, num_steps_units: u16) -> Self {
+        Self {
+            window,
+            num_steps,
+            num_steps_units,
+        }
+    }
+
+    pub fn window(&self) -> Rect {
+        self.window
+    }
+
+    /// Returns the number of steps over which the device moves to the new window.
+    pub fn num_steps(&self) -> u16 {
+        self.num_steps
+    }
+
+    /// Returns the raw `bmNumStepsUnits` value: 0 if the steps are video frames, 1 if they are
+    /// milliseconds.
+    pub fn num_steps_units(&self) -> u16 {
+        self.num_steps_units
+    }
+}
+
+impl ControlValue for DigitalWindow {
+    type Buf = [u8; 12];
+
+    fn decode(buf: &[u8]) -> Self {
+        Self {
+            window: Rect::decode(&buf[0..8]),
+            num_steps: u16::from_le_bytes([buf[8], buf[9]]),
+            num_steps_units: u16::from_le_bytes([buf[10], buf[11]]),
+        }
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        self.window.encode(&mut buf[0..8]);
+        buf[8..10].copy_from_slice(&self.num_steps.to_le_bytes());
+        buf[10..12].copy_from_slice(&self.num_steps_units.to_le_bytes());
+    }
+}
+
+/// Value of the Digital Region of Interest Control (UVC 1.5).
+#[derive(Debug, Clone, Copy)]
+pub struct RegionOfInterest {
+    region: Rect,
+    auto_controls: u16,
+}
+
+impl RegionOfInterest {
+    pub fn new(region: Rect, auto_controls: u16) -> Self {
+        Self {
+            region,
+            auto_controls,
+        }
+    }
+
+    pub fn region(&self) -> Rect {
+        self.region
+    }
+
+    /// Returns the raw `bmAutoControls` bitmap, which selects the automatic controls (eg. auto
+    /// exposure, auto focus) that take the region into account.
+    pub fn auto_controls(&self) -> u16 {
+        self.auto_controls
+    }
+}
+
+impl ControlValue for RegionOfInterest {
+    type Buf = [u8; 10];
+
+    fn decode(buf: &[u8]) -> Self {
+        Self {
+            region: Rect::decode(&buf[0..8]),
+            auto_controls: u16::from_le_bytes([buf[8], buf[9]]),
+        }
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        self.region.encode(&mut buf[0..8]);
+        buf[8..10].copy_from_slice(&self.auto_controls.to_le_bytes());
+    }
+}
+
+primitive_enum! {
+    /// Mode of the Focus, Auto Control on devices that report more than two states.
+    ///
+    /// UVC only defines the values 0 (manual focus) and 1 (automatic focus), which the
+    /// [`FocusAuto`][crate::camera::FocusAuto] control exposes as a `bool`. Some UVC 1.5 devices
+    /// report a `GET_MAX` of 2 to distinguish continuous autofocus from a one-shot focus
+    /// adjustment. Since that value is not defined by UVC, this mode is only accessible through
+    /// [`CameraTerminal::read_focus_auto_mode`].
+    ///
+    /// [`CameraTerminal::read_focus_auto_mode`]: crate::camera::CameraTerminal::read_focus_auto_mode
+    pub enum FocusAutoMode: u8 {
+        Off = 0,
+        Auto = 1,
+        Continuous = 2,
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 #[non_exhaustive]
 pub enum FocusSimple {
@@ -274,6 +780,10 @@ impl ControlValue for FocusSimple {
     }
 }
 
+/// Video Probe and Commit Controls (4.3.1.1).
+///
+/// This is the layout shared by the `PROBE` and `COMMIT` streaming controls, and the only
+/// definition of it in this crate.
 #[derive(Default, AsBytes, FromBytes, Debug, Clone, Copy)]
 #[repr(C, packed)]
 #[allow(non_snake_case)]
@@ -289,10 +799,11 @@ pub struct ProbeCommitControls {
     pub wDelay: u16,
     pub dwMaxVideoFrameSize: u32,
     pub dwMaxPayloadTransferSize: u32,
-    // FIXME: there's a MAJOR bug in the Leap Motion firmware that will make the device fail when
-    // the fields below are included, presumably because it cannot handle more data than it expects.
-    // The effect is that `GET_CUR(PROBE)` returns a 0 value in `dwFrameInterval` instead of the
-    // value sent by the preceding `SET_CUR(PROBE)`.
+    // NOTE: the Leap Motion firmware will fail when the fields below are included, presumably
+    // because it cannot handle more data than it expects. The effect is that `GET_CUR(PROBE)`
+    // returns a 0 value in `dwFrameInterval` instead of the value sent by the preceding
+    // `SET_CUR(PROBE)`. Stream negotiation uses `probe_commit_len` to only send these fields to
+    // devices implementing a UVC version that defines them.
     /*pub dwClockFrequency: u32,
     pub bmFramingInfo: u8,
     pub bPreferedVersion: u8, // (sic)
@@ -306,6 +817,56 @@ pub struct ProbeCommitControls {
     pub bmLayoutPerStream: u64,*/
 }
 
+/// Summarizes the negotiated parameters in human-readable units.
+impl fmt::Display for ProbeCommitControls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Copy the fields out of the packed struct before taking references to them.
+        let (format, frame) = (self.bFormatIndex, self.bFrameIndex);
+        let interval = self.dwFrameInterval;
+        let (frame_size, payload_size) = (self.dwMaxVideoFrameSize, self.dwMaxPayloadTransferSize);
+
+        write!(
+            f,
+            "format {}, frame {}, interval {} * 100 ns (",
+            format, frame, interval
+        )?;
+        if interval == 0 {
+            f.write_str("unspecified")?;
+        } else {
+            write!(f, "{:.2} fps", 10_000_000.0 / f64::from(interval))?;
+        }
+        write!(
+            f,
+            "), max frame size {:.1} KiB, max payload size {:.1} KiB",
+            f64::from(frame_size) / 1024.0,
+            f64::from(payload_size) / 1024.0,
+        )
+    }
+}
+
+// UVC 1.0 devices report a `GET_LEN` of 26 Bytes for the probe and commit controls.
+const _: () = assert!(std::mem::size_of::<ProbeCommitControls>() == 26);
+
+/// Length of the probe and commit controls in UVC 1.1 (up to and including `bMaxVersion`).
+const PROBE_COMMIT_LEN_1_1: usize = 34;
+
+/// Length of the probe and commit controls in UVC 1.5.
+pub(crate) const PROBE_COMMIT_LEN_MAX: usize = 48;
+
+/// Returns the length of the probe and commit controls a device implementing `version` expects.
+///
+/// Only the first 26 Bytes are represented by [`ProbeCommitControls`]. Sending a control of the
+/// wrong length can be accepted by the device but cause it to misbehave, so this has to match.
+pub(crate) fn probe_commit_len(version: BcdVersion) -> usize {
+    if version >= BcdVersion::UVC_1_5 {
+        PROBE_COMMIT_LEN_MAX
+    } else if version >= BcdVersion::UVC_1_1 {
+        PROBE_COMMIT_LEN_1_1
+    } else {
+        std::mem::size_of::<ProbeCommitControls>()
+    }
+}
+
 impl ControlValue for ProbeCommitControls {
     type Buf = ProbeCommitControlsBuf;
 
@@ -334,6 +895,66 @@ impl AsMut<[u8]> for ProbeCommitControlsBuf {
     }
 }
 
+/// Video Still Probe and Still Commit Controls (4.3.1.2).
+///
+/// After `SET_CUR(STILL_PROBE)`, `GET_CUR(STILL_PROBE)` reports the buffer sizes the device needs
+/// for the still image, which can be considerably larger than those of the video stream.
+#[derive(Default, AsBytes, FromBytes, Debug, Clone, Copy)]
+#[repr(C, packed)]
+#[allow(non_snake_case)]
+pub struct StillProbeCommitControls {
+    pub bFormatIndex: u8,
+    pub bFrameIndex: u8,
+    pub bCompressionIndex: u8,
+    pub dwMaxVideoFrameSize: u32,
+    pub dwMaxPayloadTransferSize: u32,
+}
+
+const _: () = assert!(std::mem::size_of::<StillProbeCommitControls>() == 11);
+
+impl ControlValue for StillProbeCommitControls {
+    type Buf = [u8; std::mem::size_of::<StillProbeCommitControls>()];
+
+    fn decode(buf: &[u8]) -> Self {
+        Self::read_from(buf).expect("couldn't decode `StillProbeCommitControls`")
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(self.as_bytes());
+    }
+}
+
+bitflags! {
+    /// Capabilities and state of a control, as reported by a `GET_INFO` request.
+    ///
+    /// ```
+    /// use ruvc::control::ControlInfo;
+    ///
+    /// // A control that can be read and written, and is currently disabled by an automatic mode.
+    /// let info = ControlInfo::from_bits_truncate(0b0000_0111);
+    /// assert!(info.contains(ControlInfo::SUPPORTS_GET | ControlInfo::SUPPORTS_SET));
+    /// assert!(info.contains(ControlInfo::DISABLED));
+    /// assert!(!info.contains(ControlInfo::AUTOUPDATE));
+    /// ```
+    pub struct ControlInfo: u8 {
+        /// The control supports `GET_*` requests.
+        const SUPPORTS_GET = 1 << 0;
+        /// The control supports `SET_CUR` requests.
+        const SUPPORTS_SET = 1 << 1;
+        /// The control is temporarily disabled because an automatic mode is active.
+        const DISABLED = 1 << 2;
+        /// The device may change the control's value on its own, and reports changes via its
+        /// interrupt endpoint.
+        const AUTOUPDATE = 1 << 3;
+        /// The device completes `SET_CUR` requests asynchronously, reporting completion via its
+        /// interrupt endpoint.
+        const ASYNCHRONOUS = 1 << 4;
+        /// The control is disabled because it is incompatible with the committed stream
+        /// parameters (UVC 1.5).
+        const DISABLED_BY_COMMIT_STATE = 1 << 5;
+    }
+}
+
 bitflags! {
     #[derive(Default, AsBytes, FromBytes)]
     #[repr(transparent)]
@@ -1,8 +1,10 @@
+use std::{fmt, thread, time::Duration};
+
 use crate::{
     error::*,
-    topo::{self, StreamingInterfaceDesc, Topology},
+    topo::{self, parse::ParseWarnings, ParseWarning, StreamingInterfaceDesc, Topology},
     util::split_descriptors,
-    Result,
+    Error, ListOptions, Result, UvcDeviceDesc,
 };
 use rusb::{Context, Device, TransferType};
 use zerocopy::FromBytes;
@@ -20,12 +22,19 @@ const UVC_INTERF_SUBCLASS_CONTROL: u8 = 1;
 const UVC_INTERF_SUBCLASS_STREAMING: u8 = 2;
 
 const DESC_TYPE_IAD: u8 = 11;
+const DESC_TYPE_CS_ENDPOINT: u8 = 0x25;
+
+const EP_DESC_SUBTYPE_INTERRUPT: u8 = 0x03;
 
 /// Contains information needed to communicate with a UVC device, extracted from the device, configuration, and interface descriptors.
 #[derive(Debug)]
 pub(crate) struct UvcInfo {
+    /// `bConfigurationValue` of the configuration containing the UVC function.
+    pub(crate) configuration: u8,
     pub(crate) control_interface: ControlInterface,
     pub(crate) streaming_interfaces: Vec<StreamingInterfaceDesc>,
+    /// Spec violations found while parsing the descriptors.
+    pub(crate) warnings: Vec<ParseWarning>,
 }
 
 #[derive(Debug)]
@@ -33,9 +42,105 @@ pub(crate) struct ControlInterface {
     pub(crate) interface_number: u8,
     /// Interrupt endpoint of the Video Control interface. Optional.
     pub(crate) control_interrupt_ep: Option<u8>,
+    /// `wMaxTransferSize` from the class-specific interrupt endpoint descriptor, if present.
+    ///
+    /// This is the size of the largest status packet the interrupt endpoint will send.
+    pub(crate) interrupt_max_transfer_size: Option<u16>,
     pub(crate) topo: Topology,
 }
 
+/// Identifies a USB device examined by [`list_all`][crate::list_all].
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceInfo {
+    bus_number: u8,
+    address: u8,
+    vendor_id: u16,
+    product_id: u16,
+}
+
+impl DeviceInfo {
+    pub(crate) fn new(device: &Device<Context>) -> Self {
+        // unwrap: always succeeds
+        let desc = device.device_descriptor().unwrap();
+        Self {
+            bus_number: device.bus_number(),
+            address: device.address(),
+            vendor_id: desc.vendor_id(),
+            product_id: desc.product_id(),
+        }
+    }
+
+    pub fn bus_number(&self) -> u8 {
+        self.bus_number
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    pub fn vendor_id(&self) -> u16 {
+        self.vendor_id
+    }
+
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+}
+
+impl fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bus {:03} Device {:03} {:04x}:{:04x}",
+            self.bus_number, self.address, self.vendor_id, self.product_id
+        )
+    }
+}
+
+/// Outcome of examining a USB device with [`list_all`][crate::list_all].
+#[derive(Debug)]
+pub enum DetectionResult {
+    /// The device is a supported UVC device.
+    Uvc(UvcDeviceDesc),
+    /// The device is not a (supported) UVC device.
+    NotUvc(NotUvcReason),
+    /// The device looks like a UVC device, but examining it failed (eg. because its descriptors
+    /// are malformed).
+    Error(Error),
+}
+
+/// The reason why a USB device was not detected as a UVC device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NotUvcReason {
+    /// The device class does not indicate the presence of an Interface Association Descriptor,
+    /// which UVC devices are required to use.
+    NotIadDevice,
+    /// The device has more than one configuration, which is not supported.
+    MultipleConfigurations(u8),
+    /// The device class indicates an Interface Association Descriptor, but none was found.
+    MissingIad,
+    /// The device's Interface Association Descriptor is not for a video function.
+    NotVideoFunction,
+}
+
+impl fmt::Display for NotUvcReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotUvcReason::NotIadDevice => f.write_str("device class does not indicate an IAD"),
+            NotUvcReason::MultipleConfigurations(n) => write!(
+                f,
+                "device has {} configurations, only devices with 1 are supported",
+                n
+            ),
+            NotUvcReason::MissingIad => {
+                f.write_str("found no IAD despite device class indicating that there is one")
+            }
+            NotUvcReason::NotVideoFunction => f.write_str("IAD is not for a video function"),
+        }
+    }
+}
+
 #[derive(Debug, FromBytes)]
 #[repr(C)]
 #[allow(non_snake_case)]
@@ -50,12 +155,61 @@ struct InterfaceAssociationDescriptor {
     iFunction: u8,
 }
 
-pub(crate) fn detect_uvc(device: &Device<Context>) -> Result<Option<UvcInfo>> {
+/// Number of attempts made by [`retry_transient`].
+const DESCRIPTOR_READ_ATTEMPTS: u32 = 3;
+
+/// Calls `read` until it succeeds or fails with a non-transient error, with exponential backoff.
+///
+/// Descriptor reads of freshly enumerated devices (or devices behind flaky hubs) can fail with
+/// `Io` or `Busy` errors, which would cause the device to be skipped entirely.
+fn retry_transient<T>(mut read: impl FnMut() -> rusb::Result<T>) -> rusb::Result<T> {
+    let mut backoff = Duration::from_millis(10);
+    let mut attempt = 1;
+    loop {
+        match read() {
+            Err(e @ (rusb::Error::Io | rusb::Error::Busy))
+                if attempt < DESCRIPTOR_READ_ATTEMPTS =>
+            {
+                log::debug!(
+                    "reading descriptor failed ({}), retrying in {:?}",
+                    e,
+                    backoff
+                );
+                thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Scans a block of "extra" descriptor bytes for an Interface Association Descriptor.
+fn find_iad(extra: &[u8], warnings: &ParseWarnings) -> Option<InterfaceAssociationDescriptor> {
+    split_descriptors(extra, warnings).find_map(|(desc_ty, data)| {
+        if desc_ty == DESC_TYPE_IAD {
+            match InterfaceAssociationDescriptor::read_from_prefix(data) {
+                Some(desc) => Some(desc),
+                None => {
+                    warnings.warn(format!("failed to parse IAD from {:x?}", data));
+                    None
+                }
+            }
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the [`UvcInfo`] of `device`, or the reason why it isn't a supported UVC device.
+pub(crate) fn detect_uvc(
+    device: &Device<Context>,
+    options: &ListOptions,
+) -> Result<std::result::Result<UvcInfo, NotUvcReason>> {
     // UVC uses an Interface Association Descriptor (IAD) and the corresponding device class.
 
-    let device_desc = device
-        .device_descriptor()
-        .during(Action::AccessingDeviceDescriptor)?;
+    let device_desc =
+        retry_transient(|| device.device_descriptor()).during(Action::AccessingDeviceDescriptor)?;
 
     log::trace!(
         "Bus {:03} Device {:03} {:04x}:{:04x}",
@@ -70,7 +224,7 @@ pub(crate) fn detect_uvc(device: &Device<Context>) -> Result<Option<UvcInfo>> {
         || device_desc.protocol_code() != IAD_DEVICE_PROTOCOL
     {
         log::trace!("not an IAD device");
-        return Ok(None);
+        return Ok(Err(NotUvcReason::NotIadDevice));
     }
 
     if device_desc.num_configurations() != 1 {
@@ -78,32 +232,30 @@ pub(crate) fn detect_uvc(device: &Device<Context>) -> Result<Option<UvcInfo>> {
             "device has {} configurations, we can only handle 1",
             device_desc.num_configurations()
         );
-        return Ok(None);
+        return Ok(Err(NotUvcReason::MultipleConfigurations(
+            device_desc.num_configurations(),
+        )));
     }
 
-    let config_desc = device
-        .config_descriptor(0)
+    let config_desc = retry_transient(|| device.config_descriptor(0))
         .during(Action::AccessingDeviceDescriptor)?;
 
-    let iad = split_descriptors(config_desc.extra()).find_map(|(desc_ty, data)| {
-        if desc_ty == DESC_TYPE_IAD {
-            match InterfaceAssociationDescriptor::read_from_prefix(data) {
-                Some(desc) => Some(desc),
-                None => {
-                    log::warn!("failed to parse IAD from {:x?}", data);
-                    None
-                }
-            }
-        } else {
-            None
-        }
+    let warnings = ParseWarnings::default();
+    let iad = find_iad(config_desc.extra(), &warnings).or_else(|| {
+        // Some devices have the IAD show up after the first interface descriptor, so libusb
+        // attaches it to that interface's `extra` bytes instead of the configuration's.
+        config_desc.interfaces().find_map(|interface| {
+            interface
+                .descriptors()
+                .find_map(|desc| find_iad(desc.extra(), &warnings))
+        })
     });
 
     let iad = match iad {
         Some(iad) => iad,
         None => {
             log::warn!("found no IAD despite device class indicating that there is one");
-            return Ok(None);
+            return Ok(Err(NotUvcReason::MissingIad));
         }
     };
 
@@ -114,11 +266,28 @@ pub(crate) fn detect_uvc(device: &Device<Context>) -> Result<Option<UvcInfo>> {
         || iad.bFunctionProtocol != UVC_IAD_PROTOCOL
     {
         log::trace!("not a video device");
-        return Ok(None);
+        return Ok(Err(NotUvcReason::NotVideoFunction));
     }
 
     let first_interface = iad.bFirstInterface;
-    let last_interface = first_interface + iad.bInterfaceCount - 1;
+    if iad.bInterfaceCount == 0 {
+        return err(
+            "IAD declares an interface count of 0",
+            Action::ParsingDescriptor(None),
+        );
+    }
+    let last_interface = match first_interface.checked_add(iad.bInterfaceCount - 1) {
+        Some(last) => last,
+        None => {
+            return err(
+                format!(
+                    "IAD interface range overflows (first interface {}, {} interfaces)",
+                    first_interface, iad.bInterfaceCount
+                ),
+                Action::ParsingDescriptor(None),
+            )
+        }
+    };
     let mut control_interface = None;
     let mut streaming_interfaces = Vec::new();
     for interface in config_desc.interfaces() {
@@ -131,7 +300,7 @@ pub(crate) fn detect_uvc(device: &Device<Context>) -> Result<Option<UvcInfo>> {
             if desc.class_code() != UVC_INTERF_CLASS {
                 return err(
                     format!("interface uses unexpected class code {}", desc.class_code()),
-                    Action::AccessingDeviceDescriptor,
+                    Action::ParsingDescriptor(None),
                 );
             }
 
@@ -140,7 +309,7 @@ pub(crate) fn detect_uvc(device: &Device<Context>) -> Result<Option<UvcInfo>> {
                     if control_interface.is_some() {
                         return err(
                             format!("device lists more than one control interface"),
-                            Action::AccessingDeviceDescriptor,
+                            Action::ParsingDescriptor(None),
                         );
                     }
 
@@ -150,41 +319,56 @@ pub(crate) fn detect_uvc(device: &Device<Context>) -> Result<Option<UvcInfo>> {
                                 "control interface has {} endpoints, only 1 is allowed",
                                 desc.num_endpoints()
                             ),
-                            Action::AccessingDeviceDescriptor,
+                            Action::ParsingDescriptor(None),
                         );
                     }
 
-                    let interrupt_ep = match desc.endpoint_descriptors().next() {
+                    let (interrupt_ep, interrupt_max_transfer_size) = match desc
+                        .endpoint_descriptors()
+                        .next()
+                    {
                         Some(ep) => {
                             if ep.transfer_type() != TransferType::Interrupt {
                                 return err(
-                                    format!("control interface has {:?} endpoint, only interrupt EPs are allowed", ep.transfer_type()),
-                                    Action::AccessingDeviceDescriptor,
-                                );
+                                        format!("control interface has {:?} endpoint, only interrupt EPs are allowed", ep.transfer_type()),
+                                        Action::ParsingDescriptor(None),
+                                    );
                             }
 
-                            Some(ep.address())
+                            // The class-specific descriptor is absent on some devices (eg. the
+                            // Leap Motion).
+                            let max_transfer_size = ep.extra().and_then(|extra| {
+                                parse_interrupt_max_transfer_size(extra, &warnings)
+                            });
+                            (Some(ep.address()), max_transfer_size)
                         }
-                        None => None,
+                        None => (None, None),
                     };
 
-                    let topo = topo::parse::parse_control_desc(&desc)?;
+                    let topo =
+                        topo::parse::parse_control_desc(&desc, options.repair_padding, &warnings)?;
 
                     control_interface = Some(ControlInterface {
                         interface_number: desc.interface_number(),
                         control_interrupt_ep: interrupt_ep,
+                        interrupt_max_transfer_size,
                         topo,
                     });
                 }
                 UVC_INTERF_SUBCLASS_STREAMING => {
-                    streaming_interfaces.push(topo::parse::parse_streaming_descriptor(&desc)?);
+                    streaming_interfaces.push(topo::parse::parse_streaming_descriptor(
+                        &interface,
+                        &desc,
+                        options.repair_padding,
+                        &warnings,
+                    )?);
                 }
                 e => {
-                    log::warn!(
+                    warnings.warn(format!(
                         "interface {} uses unexpected subclass code {}, ignoring it",
                         interface.number(),
                         e
-                    );
+                    ));
                 }
             }
         }
@@ -195,13 +379,66 @@ pub(crate) fn detect_uvc(device: &Device<Context>) -> Result<Option<UvcInfo>> {
         None => {
             return err(
                 format!("device does not have a UVC control interface"),
-                Action::AccessingDeviceDescriptor,
+                Action::ParsingDescriptor(None),
             )
         }
     };
 
-    Ok(Some(UvcInfo {
+    Ok(Ok(UvcInfo {
+        configuration: config_desc.number(),
         control_interface,
         streaming_interfaces,
+        warnings: warnings.into_vec(),
     }))
 }
+
+/// Extracts `wMaxTransferSize` from the class-specific VC Interrupt Endpoint Descriptor.
+fn parse_interrupt_max_transfer_size(extra: &[u8], warnings: &ParseWarnings) -> Option<u16> {
+    split_descriptors(extra, warnings).find_map(|(desc_ty, data)| match data {
+        [_, _, EP_DESC_SUBTYPE_INTERRUPT, lo, hi, ..] if desc_ty == DESC_TYPE_CS_ENDPOINT => {
+            Some(u16::from_le_bytes([*lo, *hi]))
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `extra` bytes of a Video Control interface whose IAD ended up between its
+    /// class-specific descriptors.
+    #[rustfmt::skip]
+    const VC_EXTRA_WITH_IAD: &[u8] = &[
+        // VC_HEADER
+        0x0d, 0x24, 0x01, 0x00, 0x01, 0x4f, 0x00, 0x80, 0xc3, 0xc9, 0x01, 0x01, 0x01,
+        // VC_INPUT_TERMINAL (camera)
+        0x12, 0x24, 0x02, 0x01, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+        0x2e, 0x0a, 0x02,
+        // Interface Association Descriptor
+        0x08, 0x0b, 0x00, 0x02, 0x0e, 0x03, 0x00, 0x05,
+        // VC_OUTPUT_TERMINAL
+        0x09, 0x24, 0x03, 0x04, 0x01, 0x01, 0x00, 0x01, 0x00,
+    ];
+
+    #[test]
+    fn iad_in_interface_extra() {
+        let warnings = ParseWarnings::default();
+        let iad = find_iad(VC_EXTRA_WITH_IAD, &warnings).unwrap();
+        assert_eq!(iad.bFirstInterface, 0);
+        assert_eq!(iad.bInterfaceCount, 2);
+        assert_eq!(iad.bFunctionClass, UVC_IAD_CLASS);
+        assert_eq!(iad.bFunctionSubClass, UVC_IAD_SUBCLASS);
+        assert_eq!(iad.bFunctionProtocol, UVC_IAD_PROTOCOL);
+        assert_eq!(iad.iFunction, 5);
+        assert!(warnings.into_vec().is_empty());
+    }
+
+    #[test]
+    fn no_iad() {
+        // Only the VC_HEADER and the camera terminal.
+        let warnings = ParseWarnings::default();
+        assert!(find_iad(&VC_EXTRA_WITH_IAD[..31], &warnings).is_none());
+        assert!(warnings.into_vec().is_empty());
+    }
+}
@@ -16,6 +16,29 @@ impl Error {
     pub(crate) fn is_usb_timeout(&self) -> bool {
         matches!(&self.kind, ErrorKind::Rusb(rusb::Error::Timeout))
     }
+
+    pub(crate) fn is_usb_error(&self) -> bool {
+        matches!(&self.kind, ErrorKind::Rusb(_))
+    }
+
+    /// Returns whether this error was caused by malformed or unsupported UVC descriptors.
+    ///
+    /// Such errors are not caused by a failed USB operation and will not go away when retrying,
+    /// but a vendor-specific fallback might still be able to handle the device.
+    pub fn is_descriptor_error(&self) -> bool {
+        matches!(&self.action, Some(Action::ParsingDescriptor(_)))
+    }
+
+    /// Returns the location of the descriptor that caused this error.
+    ///
+    /// Returns `None` if this is not a descriptor error, or if it isn't caused by a specific
+    /// descriptor (eg. because a required descriptor is missing).
+    pub fn descriptor_location(&self) -> Option<DescriptorLocation> {
+        match &self.action {
+            Some(Action::ParsingDescriptor(loc)) => *loc,
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -84,10 +107,76 @@ pub(crate) enum Action {
     EnumeratingDevices,
     OpeningDevice,
     ReadingDeviceString,
-    ReadingControl,
-    WritingControl,
+    ReadingControl(ControlAddress),
+    WritingControl(ControlAddress),
+    /// Parsing or validating UVC descriptors, optionally locating the offending descriptor.
+    ParsingDescriptor(Option<DescriptorLocation>),
     StreamNegotiation,
-    StreamRead,
+    StartingStream,
+    StreamRead {
+        endpoint: u8,
+    },
+    ParsingPayloadHeader,
+    ReadingStatus,
+    ReadingStillImage,
+    DecodingFrame,
+}
+
+/// Locates a descriptor in the raw class-specific descriptor data of an interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DescriptorLocation {
+    pub(crate) ty: u8,
+    pub(crate) subtype: Option<u8>,
+    /// Byte offset of the descriptor in the interface's class-specific descriptor data.
+    pub(crate) offset: usize,
+}
+
+impl DescriptorLocation {
+    /// Returns the `bDescriptorType` of the descriptor.
+    pub fn descriptor_type(&self) -> u8 {
+        self.ty
+    }
+
+    /// Returns the `bDescriptorSubtype` of the descriptor, or `None` if the descriptor is too
+    /// short to contain one.
+    pub fn descriptor_subtype(&self) -> Option<u8> {
+        self.subtype
+    }
+
+    /// Returns the Byte offset of the descriptor in the interface's class-specific descriptor
+    /// data.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for DescriptorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "descriptor type {:#04x}", self.ty)?;
+        if let Some(subtype) = self.subtype {
+            write!(f, ", subtype {:#04x}", subtype)?;
+        }
+        write!(f, " at offset {}", self.offset)
+    }
+}
+
+/// Identifies the control an entity control request was addressed to.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ControlAddress {
+    pub(crate) interface: u8,
+    pub(crate) entity: u8,
+    pub(crate) selector: u8,
+}
+
+impl fmt::Display for ControlAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "control {:#04x} on ", self.selector)?;
+        if self.entity == 0 {
+            write!(f, "interface {}", self.interface)
+        } else {
+            write!(f, "entity {} (interface {})", self.entity, self.interface)
+        }
+    }
 }
 
 impl fmt::Display for Action {
@@ -97,10 +186,25 @@ impl fmt::Display for Action {
             Action::EnumeratingDevices => "enumerating USB devices",
             Action::OpeningDevice => "opening UVC device",
             Action::ReadingDeviceString => "reading device strings",
-            Action::ReadingControl => "reading a device control",
-            Action::WritingControl => "writing a device control",
+            Action::ReadingControl(addr) => return write!(f, "reading {}", addr),
+            Action::WritingControl(addr) => return write!(f, "writing {}", addr),
+            Action::ParsingDescriptor(None) => "parsing UVC descriptors",
+            Action::ParsingDescriptor(Some(loc)) => {
+                return write!(f, "parsing UVC descriptor ({})", loc)
+            }
             Action::StreamNegotiation => "negotiating stream parameters",
-            Action::StreamRead => "reading from the video stream",
+            Action::StartingStream => "starting the video stream",
+            Action::StreamRead { endpoint } => {
+                return write!(
+                    f,
+                    "reading from the video stream (endpoint {:#04x})",
+                    endpoint
+                )
+            }
+            Action::ParsingPayloadHeader => "parsing a payload header",
+            Action::ReadingStatus => "reading a status packet",
+            Action::ReadingStillImage => "reading from the still image endpoint",
+            Action::DecodingFrame => "decoding a video frame",
         };
         f.write_str(s)
     }
@@ -0,0 +1,54 @@
+use crate::{
+    request::Request,
+    topo::{ExtensionUnitDesc, ExtensionUnitId},
+    Result, UvcDevice,
+};
+
+/// Grants access to an extension unit.
+///
+/// The controls of extension units are vendor-specific, so they are addressed by their raw
+/// selector and accessed as raw Bytes. Their length can be queried with
+/// [`ExtensionUnit::read_control_len`].
+pub struct ExtensionUnit<'a> {
+    device: &'a UvcDevice,
+    desc: &'a ExtensionUnitDesc,
+}
+
+impl<'a> ExtensionUnit<'a> {
+    pub(crate) fn new(device: &'a UvcDevice, id: ExtensionUnitId) -> Self {
+        let desc = device.topology().extension_unit_by_id(id);
+
+        Self { device, desc }
+    }
+
+    pub fn desc(&self) -> &'a ExtensionUnitDesc {
+        self.desc
+    }
+
+    /// Queries the length of the control `control_selector` in Bytes with a `GET_LEN` request.
+    pub fn read_control_len(&self, control_selector: u8) -> Result<u16> {
+        self.device
+            .read_control_len(self.desc.id().as_raw(), control_selector)
+    }
+
+    /// Performs a `GET_*` `request` on the control `control_selector`, returning the number of
+    /// Bytes the device returned.
+    ///
+    /// If the device returns fewer Bytes than `buf` can hold, the rest of `buf` is zeroed. Returns
+    /// an error if `request` is not a `GET_*` request.
+    pub fn read_control_raw(
+        &self,
+        control_selector: u8,
+        request: Request,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        self.device
+            .get_entity_control(self.desc.id().as_raw(), control_selector, request, buf)
+    }
+
+    /// Sets the control `control_selector` to `data` with a `SET_CUR` request.
+    pub fn set_control_raw(&mut self, control_selector: u8, data: &[u8]) -> Result<()> {
+        self.device
+            .set_entity(self.desc.id().as_raw(), control_selector, data)
+    }
+}
@@ -0,0 +1,282 @@
+//! GUIDs of well-known video formats.
+//!
+//! Uncompressed and frame-based format descriptors identify their format by a GUID. Most formats
+//! use a GUID derived from their FourCC code, which is stored in the first 4 Bytes. The constants
+//! in this module can be compared against [`FormatUncompressed::format`], or converted to a
+//! [`PixelFormat`]. Frames of the single-channel formats can be accessed with [`GreyImage`].
+//!
+//! [`FormatUncompressed::format`]: crate::topo::FormatUncompressed::format
+
+use uuid::Uuid;
+
+use crate::{
+    error::{err, Action},
+    Result,
+};
+
+/// `YUY2`: Packed YUV 4:2:2 (YUYV).
+pub const YUY2: Uuid = Uuid::from_u128(0x32595559_0000_0010_8000_00aa00389b71);
+
+/// `UYVY`: Packed YUV 4:2:2 (UYVY).
+pub const UYVY: Uuid = Uuid::from_u128(0x59565955_0000_0010_8000_00aa00389b71);
+
+/// `NV12`: Planar YUV 4:2:0 with an interleaved UV plane.
+pub const NV12: Uuid = Uuid::from_u128(0x3231564e_0000_0010_8000_00aa00389b71);
+
+/// `M420`: YUV 4:2:0 with two lines of Y followed by one line of interleaved UV.
+pub const M420: Uuid = Uuid::from_u128(0x3032344d_0000_0010_8000_00aa00389b71);
+
+/// `I420`: Planar YUV 4:2:0 (Y, U, V planes).
+pub const I420: Uuid = Uuid::from_u128(0x30323449_0000_0010_8000_00aa00389b71);
+
+/// `YV12`: Planar YUV 4:2:0 (Y, V, U planes).
+pub const YV12: Uuid = Uuid::from_u128(0x32315659_0000_0010_8000_00aa00389b71);
+
+/// `Y800`: 8-bit greyscale.
+pub const Y800: Uuid = Uuid::from_u128(0x30303859_0000_0010_8000_00aa00389b71);
+
+/// `Y8`: 8-bit greyscale (alternative FourCC used by some devices).
+pub const Y8: Uuid = Uuid::from_u128(0x20203859_0000_0010_8000_00aa00389b71);
+
+/// `Y16`: 16-bit greyscale, little-endian.
+pub const Y16: Uuid = Uuid::from_u128(0x20363159_0000_0010_8000_00aa00389b71);
+
+/// `RGBP`: 16-bit RGB 5:6:5.
+pub const RGBP: Uuid = Uuid::from_u128(0x50424752_0000_0010_8000_00aa00389b71);
+
+/// `MJPG`: Motion-JPEG, used with the frame-based format descriptors.
+pub const MJPG: Uuid = Uuid::from_u128(0x47504a4d_0000_0010_8000_00aa00389b71);
+
+/// `H264`: H.264, used with the frame-based format descriptors.
+pub const H264: Uuid = Uuid::from_u128(0x34363248_0000_0010_8000_00aa00389b71);
+
+/// `H265`: H.265, used with the frame-based format descriptors.
+pub const H265: Uuid = Uuid::from_u128(0x35363248_0000_0010_8000_00aa00389b71);
+
+/// `BGR3`: Packed 24-bit BGR, identified by the DirectShow `MEDIASUBTYPE_RGB24` GUID.
+pub const BGR3: Uuid = Uuid::from_u128(0xe436eb7d_524f_11ce_9f53_0020af0ba770);
+
+/// Pixel formats of uncompressed video that can be identified from their format GUID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PixelFormat {
+    /// Packed YUV 4:2:2 (YUYV), see [`YUY2`].
+    Yuy2,
+    /// Packed YUV 4:2:2 (UYVY), see [`UYVY`].
+    Uyvy,
+    /// Planar YUV 4:2:0 with an interleaved UV plane, see [`NV12`].
+    Nv12,
+    /// YUV 4:2:0 with two lines of Y followed by one line of interleaved UV, see [`M420`].
+    M420,
+    /// Planar YUV 4:2:0 (Y, U, V planes), see [`I420`].
+    I420,
+    /// Planar YUV 4:2:0 (Y, V, U planes), see [`YV12`].
+    Yv12,
+    /// 8-bit greyscale, see [`Y800`] and [`Y8`].
+    Grey,
+    /// 16-bit greyscale, little-endian, see [`Y16`].
+    Y16,
+    /// 16-bit RGB 5:6:5, see [`RGBP`].
+    Rgb565,
+    /// Packed 24-bit BGR, see [`BGR3`].
+    Bgr24,
+}
+
+impl PixelFormat {
+    /// Looks up the pixel format identified by a format GUID.
+    ///
+    /// Returns `None` if the GUID doesn't belong to a known uncompressed format.
+    ///
+    /// ```
+    /// use ruvc::formats::{self, PixelFormat};
+    ///
+    /// assert_eq!(PixelFormat::from_guid(&formats::YUY2), Some(PixelFormat::Yuy2));
+    /// assert_eq!(PixelFormat::from_guid(&formats::NV12), Some(PixelFormat::Nv12));
+    /// assert_eq!(PixelFormat::from_guid(&formats::MJPG), None);
+    /// ```
+    pub fn from_guid(guid: &Uuid) -> Option<Self> {
+        Some(match *guid {
+            YUY2 => Self::Yuy2,
+            UYVY => Self::Uyvy,
+            NV12 => Self::Nv12,
+            M420 => Self::M420,
+            I420 => Self::I420,
+            YV12 => Self::Yv12,
+            Y800 | Y8 => Self::Grey,
+            Y16 => Self::Y16,
+            RGBP => Self::Rgb565,
+            BGR3 => Self::Bgr24,
+            _ => return None,
+        })
+    }
+}
+
+/// A single-channel image, as streamed by depth and infrared cameras.
+///
+/// This interprets a reassembled frame (eg. from
+/// [`FrameReader::next_frame`][crate::streaming_interface::FrameReader::next_frame]) of the
+/// [`PixelFormat::Grey`] or [`PixelFormat::Y16`] formats. Rows are stored without padding, each
+/// `width * bytes_per_pixel` Bytes long.
+#[derive(Debug, Clone, Copy)]
+pub struct GreyImage<'a> {
+    data: &'a [u8],
+    width: u16,
+    height: u16,
+    bytes_per_pixel: usize,
+}
+
+impl<'a> GreyImage<'a> {
+    /// Interprets `frame` as an image of `width` x `height` pixels in the single-channel
+    /// `pixel_format`.
+    ///
+    /// `pixel_format` and the resolution have to match the negotiated stream (see
+    /// [`Stream::resolution`][crate::streaming_interface::Stream::resolution]). Bytes following
+    /// the image data, which some devices use to pad their frames, are stripped. Returns an error
+    /// if `pixel_format` is not a single-channel format, or if `frame` is too short to hold the
+    /// image.
+    pub fn from_frame(
+        frame: &'a [u8],
+        pixel_format: PixelFormat,
+        width: u16,
+        height: u16,
+    ) -> Result<Self> {
+        let bytes_per_pixel = match pixel_format {
+            PixelFormat::Grey => 1,
+            PixelFormat::Y16 => 2,
+            _ => {
+                return err(
+                    format!("{:?} is not a single-channel pixel format", pixel_format),
+                    Action::DecodingFrame,
+                )
+            }
+        };
+        let len = usize::from(width) * usize::from(height) * bytes_per_pixel;
+        let data = match frame.get(..len) {
+            Some(data) => data,
+            None => {
+                return err(
+                    format!(
+                        "frame of {} Bytes is too short for a {}x{} {:?} image ({} Bytes)",
+                        frame.len(),
+                        width,
+                        height,
+                        pixel_format,
+                        len
+                    ),
+                    Action::DecodingFrame,
+                )
+            }
+        };
+        if frame.len() > len {
+            log::trace!("stripping {} Bytes of frame padding", frame.len() - len);
+        }
+
+        Ok(Self {
+            data,
+            width,
+            height,
+            bytes_per_pixel,
+        })
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the size of a pixel in Bytes (1 for [`PixelFormat::Grey`], 2 for
+    /// [`PixelFormat::Y16`]).
+    pub fn bytes_per_pixel(&self) -> usize {
+        self.bytes_per_pixel
+    }
+
+    /// Returns the image data, `height` rows of `width * bytes_per_pixel` Bytes each.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the data of row `y`.
+    ///
+    /// Panics if `y` is out of bounds.
+    pub fn row(&self, y: u16) -> &'a [u8] {
+        assert!(y < self.height, "row {} out of bounds", y);
+        let stride = usize::from(self.width) * self.bytes_per_pixel;
+        let start = usize::from(y) * stride;
+        &self.data[start..start + stride]
+    }
+
+    /// Returns the value of the pixel at `(x, y)`.
+    ///
+    /// 16-bit values are stored in little-endian order. Panics if `x` or `y` is out of bounds.
+    pub fn pixel(&self, x: u16, y: u16) -> u16 {
+        assert!(x < self.width, "column {} out of bounds", x);
+        let offset = usize::from(x) * self.bytes_per_pixel;
+        match self.row(y)[offset..offset + self.bytes_per_pixel] {
+            [value] => u16::from(value),
+            [lo, hi] => u16::from_le_bytes([lo, hi]),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::FormatGuid;
+
+    /// Every constant of this module with the pixel format it maps to.
+    const ALL: &[(&str, Uuid, Option<PixelFormat>)] = &[
+        ("YUY2", YUY2, Some(PixelFormat::Yuy2)),
+        ("UYVY", UYVY, Some(PixelFormat::Uyvy)),
+        ("NV12", NV12, Some(PixelFormat::Nv12)),
+        ("M420", M420, Some(PixelFormat::M420)),
+        ("I420", I420, Some(PixelFormat::I420)),
+        ("YV12", YV12, Some(PixelFormat::Yv12)),
+        ("Y800", Y800, Some(PixelFormat::Grey)),
+        ("Y8", Y8, Some(PixelFormat::Grey)),
+        ("Y16", Y16, Some(PixelFormat::Y16)),
+        ("RGBP", RGBP, Some(PixelFormat::Rgb565)),
+        ("MJPG", MJPG, None),
+        ("H264", H264, None),
+        ("H265", H265, None),
+        ("BGR3", BGR3, Some(PixelFormat::Bgr24)),
+    ];
+
+    #[test]
+    fn round_trip() {
+        for (name, guid, pixel_format) in ALL {
+            assert_eq!(PixelFormat::from_guid(guid), *pixel_format, "{}", name);
+
+            if *name == "BGR3" {
+                // The DirectShow GUID isn't derived from a FourCC, so it is shown in full.
+                assert_eq!(FormatGuid(guid).fourcc(), None);
+                assert_eq!(FormatGuid(guid).to_string(), guid.to_string());
+            } else {
+                assert_eq!(FormatGuid(guid).to_string(), *name);
+            }
+        }
+    }
+
+    #[test]
+    fn grey_image() {
+        // 3x2 pixels of Y16, followed by 2 Bytes of padding.
+        let frame = [1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 0x06, 0x01, 0xff, 0xff];
+        let image = GreyImage::from_frame(&frame, PixelFormat::Y16, 3, 2).unwrap();
+        assert_eq!(image.bytes_per_pixel(), 2);
+        assert_eq!(image.data().len(), 12);
+        assert_eq!(image.row(1), [4, 0, 5, 0, 0x06, 0x01]);
+        assert_eq!(image.pixel(0, 0), 1);
+        assert_eq!(image.pixel(2, 1), 0x106);
+
+        let image = GreyImage::from_frame(&frame, PixelFormat::Grey, 4, 3).unwrap();
+        assert_eq!(image.bytes_per_pixel(), 1);
+        assert_eq!(image.data().len(), 12);
+        assert_eq!(image.pixel(3, 2), 0x01);
+
+        assert!(GreyImage::from_frame(&frame, PixelFormat::Y16, 4, 2).is_err());
+        assert!(GreyImage::from_frame(&frame, PixelFormat::Yuy2, 3, 2).is_err());
+    }
+}
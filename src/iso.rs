@@ -0,0 +1,264 @@
+//! Isochronous transfers via libusb's asynchronous API, which `rusb` doesn't wrap.
+
+use std::{
+    convert::TryFrom,
+    mem,
+    os::raw::{c_int, c_uint},
+    sync::atomic::{AtomicI32, Ordering},
+    time::Duration,
+};
+
+use rusb::{
+    ffi::{self, constants::*},
+    Context, DeviceHandle, UsbContext,
+};
+
+/// A ring of isochronous IN transfers that are continuously resubmitted.
+///
+/// Packets are handed out in the order the device sent them. Each non-empty packet contains one
+/// UVC payload (including its payload header).
+pub(crate) struct IsoTransfers {
+    context: Context,
+    transfers: Vec<Transfer>,
+    packet_size: usize,
+    packets_per_transfer: usize,
+    /// Index of the transfer that is being drained or waited on.
+    current: usize,
+    /// Index of the next packet of the current transfer, or `None` if it hasn't completed yet.
+    next_packet: Option<usize>,
+}
+
+struct Transfer {
+    raw: *mut ffi::libusb_transfer,
+    buffer: Vec<u8>,
+    /// Set to 1 by the completion callback.
+    ///
+    /// Allocated via `Box::into_raw` so that the pointer handed to libusb stays valid no matter
+    /// how the `Transfer` is moved or borrowed. Freed in `IsoTransfers::drop`.
+    completed: *mut AtomicI32,
+}
+
+impl Transfer {
+    fn completed(&self) -> &AtomicI32 {
+        // Safety: the flag is only freed after the transfer itself, and is only accessed
+        // atomically.
+        unsafe { &*self.completed }
+    }
+
+    fn is_completed(&self) -> bool {
+        self.completed().load(Ordering::Acquire) != 0
+    }
+}
+
+// Safety: the raw transfers and their buffers are exclusively owned by `IsoTransfers`. libusb
+// invokes the completion callback on whichever thread is handling events on the shared `Context`,
+// which may be a thread that drives a different stream. The callback only touches the atomic
+// `completed` flag, and the buffers are only accessed by the owner after that flag was set.
+unsafe impl Send for IsoTransfers {}
+
+extern "system" fn transfer_callback(transfer: *mut ffi::libusb_transfer) {
+    // Safety: `user_data` points to the `completed` flag of the `Transfer`, which outlives the
+    // transfer.
+    unsafe {
+        (*((*transfer).user_data as *const AtomicI32)).store(1, Ordering::Release);
+    }
+}
+
+impl IsoTransfers {
+    /// Allocates and submits `num_transfers` transfers of `packets_per_transfer` packets each,
+    /// reading packets of up to `packet_size` Bytes from the isochronous IN endpoint `endpoint`.
+    pub(crate) fn start(
+        usb: &DeviceHandle<Context>,
+        endpoint: u8,
+        packet_size: usize,
+        num_transfers: usize,
+        packets_per_transfer: usize,
+        timeout: Duration,
+    ) -> rusb::Result<Self> {
+        let mut this = Self {
+            context: usb.context().clone(),
+            transfers: Vec::with_capacity(num_transfers),
+            packet_size,
+            packets_per_transfer,
+            current: 0,
+            next_packet: None,
+        };
+        let timeout = c_uint::try_from(timeout.as_millis()).unwrap_or(c_uint::MAX);
+
+        let num_packets =
+            c_int::try_from(packets_per_transfer).map_err(|_| rusb::Error::InvalidParam)?;
+        for _ in 0..num_transfers {
+            // Safety: allocating transfers has no preconditions.
+            let raw = unsafe { ffi::libusb_alloc_transfer(num_packets) };
+            if raw.is_null() {
+                return Err(rusb::Error::NoMem);
+            }
+            let mut transfer = Transfer {
+                raw,
+                buffer: vec![0; packet_size * packets_per_transfer],
+                completed: Box::into_raw(Box::new(AtomicI32::new(1))),
+            };
+            // Safety: `raw` was allocated with `num_packets` packet descriptors, and the
+            // buffer and flag are owned by `transfer`, which frees the transfer before them.
+            unsafe {
+                ffi::libusb_fill_iso_transfer(
+                    raw,
+                    usb.as_raw(),
+                    endpoint,
+                    transfer.buffer.as_mut_ptr(),
+                    transfer.buffer.len() as c_int,
+                    num_packets,
+                    transfer_callback,
+                    transfer.completed as *mut _,
+                    timeout,
+                );
+                ffi::libusb_set_iso_packet_lengths(raw, packet_size as c_uint);
+            }
+            this.transfers.push(transfer);
+            this.submit(this.transfers.len() - 1)?;
+        }
+
+        Ok(this)
+    }
+
+    fn submit(&mut self, index: usize) -> rusb::Result<()> {
+        let transfer = &self.transfers[index];
+        transfer.completed().store(0, Ordering::Release);
+        // Safety: the transfer was filled in `start` and is not currently submitted.
+        match unsafe { ffi::libusb_submit_transfer(transfer.raw) } {
+            0 => Ok(()),
+            e => {
+                // A transfer that failed to submit will never complete.
+                transfer.completed().store(1, Ordering::Release);
+                Err(error_from_libusb(e))
+            }
+        }
+    }
+
+    /// Blocks until the transfer at `index` has completed.
+    fn wait(&self, index: usize) -> rusb::Result<()> {
+        let transfer = &self.transfers[index];
+        while !transfer.is_completed() {
+            // Safety: `completed` is the flag set by the transfer's callback, and libusb only
+            // reads it while holding its event lock. The transfer's timeout guarantees that it
+            // eventually completes.
+            let res = unsafe {
+                ffi::libusb_handle_events_completed(
+                    self.context.as_raw(),
+                    transfer.completed().as_ptr(),
+                )
+            };
+            if res < 0 && res != LIBUSB_ERROR_INTERRUPTED {
+                return Err(error_from_libusb(res));
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the next non-empty packet into `buf`, returning its length.
+    ///
+    /// Returns an error if a transfer fails or times out, or if `buf` is too small for the packet.
+    pub(crate) fn read_packet(&mut self, buf: &mut [u8]) -> rusb::Result<usize> {
+        loop {
+            let index = self.current;
+            let next_packet = match self.next_packet {
+                Some(packet) => packet,
+                None => {
+                    self.wait(index)?;
+                    // Safety: the transfer has completed, so libusb doesn't access it.
+                    let status = unsafe { (*self.transfers[index].raw).status };
+                    if status != LIBUSB_TRANSFER_COMPLETED {
+                        // Resubmit, so that the stream can recover from a timeout. The other
+                        // transfers were submitted earlier, so continue with them.
+                        self.current = (index + 1) % self.transfers.len();
+                        self.submit(index)?;
+                        return Err(error_from_transfer_status(status));
+                    }
+                    0
+                }
+            };
+
+            let transfer = &self.transfers[index];
+            for packet in next_packet..self.packets_per_transfer {
+                // Safety: the transfer has completed and has `packets_per_transfer` packets.
+                let desc = unsafe { &*(*transfer.raw).iso_packet_desc.as_ptr().add(packet) };
+                let len = desc.actual_length as usize;
+                if desc.status != LIBUSB_TRANSFER_COMPLETED || len == 0 {
+                    continue;
+                }
+                if len > buf.len() {
+                    self.next_packet = Some(packet + 1);
+                    return Err(rusb::Error::Overflow);
+                }
+
+                let offset = packet * self.packet_size;
+                buf[..len].copy_from_slice(&transfer.buffer[offset..offset + len]);
+                self.next_packet = Some(packet + 1);
+                return Ok(len);
+            }
+
+            // All packets were handed out, reuse the transfer and continue with the next one.
+            self.next_packet = None;
+            self.current = (index + 1) % self.transfers.len();
+            self.submit(index)?;
+        }
+    }
+}
+
+impl Drop for IsoTransfers {
+    fn drop(&mut self) {
+        for transfer in &self.transfers {
+            if !transfer.is_completed() {
+                // Safety: the transfer is submitted. Cancellation completes it asynchronously.
+                unsafe {
+                    ffi::libusb_cancel_transfer(transfer.raw);
+                }
+            }
+        }
+        for index in 0..self.transfers.len() {
+            if let Err(e) = self.wait(index) {
+                // Freeing transfers that are still in flight would be unsound, so leak them.
+                log::error!("failed to cancel isochronous transfers: {}", e);
+                mem::forget(mem::take(&mut self.transfers));
+                return;
+            }
+        }
+        for transfer in &self.transfers {
+            // Safety: the transfer has completed and is no longer used, so neither libusb nor the
+            // callback access it or its flag anymore.
+            unsafe {
+                ffi::libusb_free_transfer(transfer.raw);
+                drop(Box::from_raw(transfer.completed));
+            }
+        }
+    }
+}
+
+fn error_from_transfer_status(status: c_int) -> rusb::Error {
+    match status {
+        LIBUSB_TRANSFER_TIMED_OUT => rusb::Error::Timeout,
+        LIBUSB_TRANSFER_STALL => rusb::Error::Pipe,
+        LIBUSB_TRANSFER_NO_DEVICE => rusb::Error::NoDevice,
+        LIBUSB_TRANSFER_OVERFLOW => rusb::Error::Overflow,
+        LIBUSB_TRANSFER_CANCELLED => rusb::Error::Interrupted,
+        _ => rusb::Error::Io,
+    }
+}
+
+fn error_from_libusb(err: c_int) -> rusb::Error {
+    match err {
+        LIBUSB_ERROR_IO => rusb::Error::Io,
+        LIBUSB_ERROR_INVALID_PARAM => rusb::Error::InvalidParam,
+        LIBUSB_ERROR_ACCESS => rusb::Error::Access,
+        LIBUSB_ERROR_NO_DEVICE => rusb::Error::NoDevice,
+        LIBUSB_ERROR_NOT_FOUND => rusb::Error::NotFound,
+        LIBUSB_ERROR_BUSY => rusb::Error::Busy,
+        LIBUSB_ERROR_TIMEOUT => rusb::Error::Timeout,
+        LIBUSB_ERROR_OVERFLOW => rusb::Error::Overflow,
+        LIBUSB_ERROR_PIPE => rusb::Error::Pipe,
+        LIBUSB_ERROR_INTERRUPTED => rusb::Error::Interrupted,
+        LIBUSB_ERROR_NO_MEM => rusb::Error::NoMem,
+        LIBUSB_ERROR_NOT_SUPPORTED => rusb::Error::NotSupported,
+        _ => rusb::Error::Other,
+    }
+}
@@ -11,20 +11,38 @@ pub mod camera;
 pub mod control;
 mod detect;
 mod error;
+pub mod extension_unit;
+pub mod formats;
+mod iso;
+pub mod payload;
 pub mod processing_unit;
+mod request;
+pub mod selector_unit;
 pub mod streaming_interface;
+mod summary;
 pub mod topo;
 
-use std::{fmt, time::Duration};
+use std::{fmt, sync::Mutex, time::Duration};
 
 use camera::CameraTerminal;
 use detect::UvcInfo;
-pub use error::Error;
+pub use detect::{DetectionResult, DeviceInfo, NotUvcReason};
 use error::*;
+pub use error::{DescriptorLocation, Error};
+use extension_unit::ExtensionUnit;
+use formats::PixelFormat;
 use processing_unit::ProcessingUnit;
-use rusb::{Context, Device, DeviceHandle, UsbContext};
-use streaming_interface::StreamingInterface;
-use topo::{CameraId, ProcessingUnitId, StreamingInterfaceDesc, StreamingInterfaceId, Topology};
+pub use request::Request;
+pub use rusb;
+use rusb::{Context, Device, DeviceHandle, Speed, UsbContext};
+use selector_unit::SelectorUnit;
+use streaming_interface::{FrameReader, StreamingInterface};
+pub use summary::DeviceSummary;
+use topo::{
+    CameraId, ExtensionUnitId, ParseWarning, ProcessingUnitId, SelectorUnitId,
+    StreamingInterfaceDesc, StreamingInterfaceId, Topology,
+};
+pub use util::BcdVersion;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -45,8 +63,9 @@ impl UvcDeviceDesc {
         self.usb.device_descriptor().unwrap().product_id()
     }
 
+    /// Opens the device with the default [`OpenOptions`].
     pub fn open(self) -> Result<UvcDevice> {
-        UvcDevice::open(self)
+        OpenOptions::new().open(self)
     }
 }
 
@@ -58,45 +77,274 @@ impl fmt::Debug for UvcDeviceDesc {
     }
 }
 
+/// Options that control how a [`UvcDevice`] is opened.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> ruvc::Result<()> {
+/// use ruvc::OpenOptions;
+///
+/// let desc = ruvc::list()?.next().unwrap();
+/// let dev = OpenOptions::new()
+///     .auto_detach_kernel_driver(false)
+///     .open(desc)?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenOptions {
+    auto_detach_kernel_driver: bool,
+}
+
+impl OpenOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self {
+            auto_detach_kernel_driver: true,
+        }
+    }
+
+    /// Sets whether kernel drivers bound to the UVC interfaces are detached automatically while
+    /// the device is open (default: `true`).
+    ///
+    /// On Linux, this unbinds the `uvcvideo` driver, which is necessary to claim the interfaces
+    /// unless the driver was unbound by other means. The driver is reattached when the device is
+    /// closed. On platforms that don't support detaching kernel drivers, this has no effect.
+    pub fn auto_detach_kernel_driver(&mut self, detach: bool) -> &mut Self {
+        self.auto_detach_kernel_driver = detach;
+        self
+    }
+
+    /// Opens the UVC device described by `desc` with these options.
+    pub fn open(&self, desc: UvcDeviceDesc) -> Result<UvcDevice> {
+        UvcDevice::open(desc, self)
+    }
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists the connected UVC devices, using the default [`ListOptions`].
 pub fn list() -> Result<impl Iterator<Item = UvcDeviceDesc>> {
-    let ctx = Context::new().during(Action::EnumeratingDevices)?;
-    let list = ctx.devices().during(Action::EnumeratingDevices)?;
-
-    let devices = list
-        .iter()
-        .filter_map(|dev| match detect::detect_uvc(&dev) {
-            Ok(Some(info)) => Some(UvcDeviceDesc {
-                usb: dev,
-                uvc_info: info,
-            }),
-            Ok(None) => None,
-            Err(e) => {
-                log::error!("{:?}: {}", dev, e);
-                None
-            }
-        })
-        .collect::<Vec<_>>();
+    ListOptions::new().list()
+}
+
+/// Examines all USB devices, reporting for each one whether it is a UVC device, and if not, why.
+///
+/// Unlike [`list`], which only returns the detected UVC devices, this is meant for diagnosing why
+/// a device is not detected. The default [`ListOptions`] are used.
+pub fn list_all() -> Result<Vec<(DeviceInfo, DetectionResult)>> {
+    ListOptions::new().list_all()
+}
+
+/// Opens the first UVC device with the given USB vendor and product ID.
+///
+/// Returns `Ok(None)` if no such device is connected. The device is opened with the default
+/// [`OpenOptions`]; use [`list`] and [`OpenOptions::open`] for more control.
+pub fn open_by_vid_pid(vid: u16, pid: u16) -> Result<Option<UvcDevice>> {
+    list()?
+        .find(|desc| desc.vendor_id() == vid && desc.product_id() == pid)
+        .map(UvcDeviceDesc::open)
+        .transpose()
+}
+
+/// Captures a single frame from the first connected UVC device.
+///
+/// This opens the first device returned by [`list`] and streams from its default streaming
+/// interface, using the first uncompressed format with a known [`PixelFormat`] at the format's
+/// default frame size and frame interval. Startup noise is skipped like with
+/// [`FrameReader::read_first_frame`].
+///
+/// Returns the frame's width, height, pixel format and data. An error is returned if no device is
+/// connected, if the device has no suitable format, or if no frame arrives within 5 seconds.
+///
+/// [`FrameReader::read_first_frame`]: streaming_interface::FrameReader::read_first_frame
+pub fn grab_one_frame() -> Result<(u16, u16, PixelFormat, Vec<u8>)> {
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    let dev = match list()?.next() {
+        Some(desc) => desc.open()?,
+        None => return err("no UVC device is connected", Action::OpeningDevice),
+    };
+    let desc = match dev.default_streaming_interface() {
+        Some(desc) => desc,
+        None => return err("device has no streaming interfaces", Action::StartingStream),
+    };
+    let (pixel_format, frame) = match desc.formats().iter().find_map(|format| {
+        let uncompressed = format.as_format_uncompressed()?;
+        let frame = desc.try_frame_by_index(format.index(), uncompressed.default_frame_index())?;
+        Some((uncompressed.pixel_format()?, frame))
+    }) {
+        Some(found) => found,
+        None => {
+            return err(
+                "device has no uncompressed format with a known pixel format",
+                Action::StartingStream,
+            )
+        }
+    };
+
+    let (width, height) = frame.resolution();
+    let fps = 1.0 / frame.default_frame_interval().as_secs_f64();
+    let mut interface = dev.streaming_interface_by_id(desc.id());
+    let stream = interface.start_stream_format(pixel_format, width, height, fps)?;
+    let mut frames = FrameReader::new(stream)?;
+    let data = frames.read_first_frame(TIMEOUT)?.to_vec();
+    Ok((width, height, pixel_format, data))
+}
+
+/// Options that control how UVC devices are detected and their descriptors parsed.
+#[derive(Debug, Clone)]
+pub struct ListOptions {
+    repair_padding: usize,
+}
+
+impl ListOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self {
+            repair_padding: topo::parse::DEFAULT_REPAIR_PADDING,
+        }
+    }
 
-    Ok(devices.into_iter())
+    /// Sets the number of zero Bytes appended to descriptors that are too short to be parsed
+    /// (default: 100).
+    ///
+    /// Many devices have descriptors that are shorter than the UVC specification requires. They
+    /// are parsed again after padding them with this many zero Bytes. Setting this to 0 disables
+    /// the repair, so that truncated descriptors are reported as errors.
+    pub fn repair_padding(&mut self, bytes: usize) -> &mut Self {
+        self.repair_padding = bytes;
+        self
+    }
+
+    /// Lists the connected UVC devices.
+    pub fn list(&self) -> Result<impl Iterator<Item = UvcDeviceDesc>> {
+        let ctx = Context::new().during(Action::EnumeratingDevices)?;
+        let list = ctx.devices().during(Action::EnumeratingDevices)?;
+
+        let devices = list
+            .iter()
+            .filter_map(|dev| match detect::detect_uvc(&dev, self) {
+                Ok(Ok(info)) => Some(UvcDeviceDesc {
+                    usb: dev,
+                    uvc_info: info,
+                }),
+                Ok(Err(_)) => None,
+                Err(e) => {
+                    log::error!("{:?}: {}", dev, e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(devices.into_iter())
+    }
+
+    /// Examines all USB devices, like [`list_all`].
+    pub fn list_all(&self) -> Result<Vec<(DeviceInfo, DetectionResult)>> {
+        let ctx = Context::new().during(Action::EnumeratingDevices)?;
+        let list = ctx.devices().during(Action::EnumeratingDevices)?;
+
+        Ok(list
+            .iter()
+            .map(|dev| {
+                let info = DeviceInfo::new(&dev);
+                let result = match detect::detect_uvc(&dev, self) {
+                    Ok(Ok(uvc_info)) => DetectionResult::Uvc(UvcDeviceDesc { usb: dev, uvc_info }),
+                    Ok(Err(reason)) => DetectionResult::NotUvc(reason),
+                    Err(e) => DetectionResult::Error(e),
+                };
+                (info, result)
+            })
+            .collect())
+    }
 }
 
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An opened UVC device.
+///
+/// `UvcDevice` is `Send` and `Sync`: the underlying libusb device handle may be used from multiple
+/// threads, and everything else it holds is plain descriptor data. This means that an opened device
+/// can be moved into a dedicated capture thread, or shared between threads by reference.
+///
+/// All accessors ([`StreamingInterface`], [`CameraTerminal`], [`ProcessingUnit`], and [`Stream`])
+/// only need a shared reference to the device, so a stream can be read on one thread while another
+/// thread adjusts controls:
+///
+/// ```no_run
+/// # fn main() -> ruvc::Result<()> {
+/// use std::io::Read;
+/// use ruvc::processing_unit::Brightness;
+///
+/// let dev = ruvc::list()?.next().unwrap().open()?;
+/// let interface = dev.default_streaming_interface().unwrap();
+/// let (format, frame) = (interface.formats()[0].index(), interface.frames()[0].index());
+/// let pu_id = dev.topology().units().iter().find_map(|u| u.as_processing_unit()).unwrap().id();
+///
+/// let mut stream = dev
+///     .streaming_interface_by_id(interface.id())
+///     .start_stream(format, frame)?;
+/// std::thread::scope(|s| {
+///     s.spawn(move || {
+///         let mut buf = vec![0; 1024];
+///         while stream.read(&mut buf).is_ok() {}
+///     });
+///
+///     dev.processing_unit_by_id(pu_id).set_control::<Brightness>(10)
+/// })?;
+/// # Ok(()) }
+/// ```
+///
+/// [`Stream`]: streaming_interface::Stream
 pub struct UvcDevice {
     usb: DeviceHandle<Context>,
     uvc_info: UvcInfo,
     timeout: Duration,
+    /// Interface numbers of the streaming interfaces with a running [`Stream`], in start order.
+    ///
+    /// [`Stream`]: streaming_interface::Stream
+    active_streams: Mutex<Vec<u8>>,
+    /// Alternate settings selected on the streaming interfaces, as `(interface, alt_setting)`
+    /// pairs. Interfaces that aren't listed use alternate setting 0.
+    alt_settings: Mutex<Vec<(u8, u8)>>,
 }
 
 impl UvcDevice {
-    fn open(desc: UvcDeviceDesc) -> Result<Self> {
+    fn open(desc: UvcDeviceDesc, options: &OpenOptions) -> Result<Self> {
         let mut usb = desc.usb.open().during(Action::OpeningDevice)?;
-        if let Err(e) = usb.set_auto_detach_kernel_driver(true) {
-            log::warn!("set_auto_detach_kernel_driver failed: {}", e);
+        if options.auto_detach_kernel_driver {
+            if rusb::supports_detach_kernel_driver() {
+                if let Err(e) = usb.set_auto_detach_kernel_driver(true) {
+                    log::warn!("set_auto_detach_kernel_driver failed: {}", e);
+                }
+            } else {
+                log::debug!("platform does not support detaching kernel drivers");
+            }
         }
 
+        // Some platforms (notably Windows and macOS) do not allow changing the configuration, so
+        // only attempt that if the device isn't already in the one containing the UVC function.
+        let required = desc.uvc_info.configuration;
         let config = usb.active_configuration().during(Action::OpeningDevice)?;
-        if config != 1 {
-            usb.set_active_configuration(1)
-                .during(Action::OpeningDevice)?;
+        if config != required {
+            if let Err(e) = usb.set_active_configuration(required) {
+                return err(
+                    format!(
+                        "device is in configuration {}, but the UVC function requires configuration {} (failed to switch: {})",
+                        config, required, e
+                    ),
+                    Action::OpeningDevice,
+                );
+            }
         }
 
         usb.claim_interface(desc.uvc_info.control_interface.interface_number)
@@ -107,14 +355,22 @@ impl UvcDevice {
         }
 
         let config = usb.active_configuration().during(Action::OpeningDevice)?;
-        if config != 1 {
-            return err("failed to claim device", Action::OpeningDevice);
+        if config != required {
+            return err(
+                format!(
+                    "device is in configuration {} after claiming interfaces, but the UVC function requires configuration {}",
+                    config, required
+                ),
+                Action::OpeningDevice,
+            );
         }
 
         Ok(UvcDevice {
             usb,
             uvc_info: desc.uvc_info,
             timeout: Duration::from_millis(1000),
+            active_streams: Mutex::new(Vec::new()),
+            alt_settings: Mutex::new(Vec::new()),
         })
     }
 
@@ -139,6 +395,7 @@ impl UvcDevice {
             entity_id,
             cs,
             data,
+            self.timeout,
         )
     }
 
@@ -148,6 +405,7 @@ impl UvcDevice {
         entity_id: u8,
         cs: u8,
         data: &[u8],
+        timeout: Duration,
     ) -> Result<()> {
         const SET_ENTITY_REQ: u8 = 0b00100001;
 
@@ -160,20 +418,44 @@ impl UvcDevice {
                 value,
                 index,
                 data,
-                self.timeout,
+                timeout,
             )
-            .during(Action::WritingControl)?;
+            .during(Action::WritingControl(ControlAddress {
+                interface,
+                entity: entity_id,
+                selector: cs,
+            }))?;
             Ok(())
         })
     }
 
-    fn read_entity(&self, entity_id: u8, request: Request, cs: u8, buf: &mut [u8]) -> Result<()> {
+    /// Returns the address of the control `cs` of the entity `entity_id`, for error reporting.
+    fn entity_control_address(&self, entity_id: u8, cs: u8) -> ControlAddress {
+        ControlAddress {
+            interface: self.uvc_info.control_interface.interface_number,
+            entity: entity_id,
+            selector: cs,
+        }
+    }
+
+    /// Performs a `GET_*` request on an entity control, returning the number of Bytes the device
+    /// returned.
+    ///
+    /// If the device returns fewer Bytes than `buf` can hold, the rest of `buf` is zeroed.
+    fn read_entity(
+        &self,
+        entity_id: u8,
+        request: Request,
+        cs: u8,
+        buf: &mut [u8],
+    ) -> Result<usize> {
         self.read_interface_entity(
             self.uvc_info.control_interface.interface_number,
             entity_id,
             request,
             cs,
             buf,
+            self.timeout,
         )
     }
 
@@ -184,24 +466,37 @@ impl UvcDevice {
         request: Request,
         cs: u8,
         buf: &mut [u8],
-    ) -> Result<()> {
+        timeout: Duration,
+    ) -> Result<usize> {
         const GET_ENTITY_REQ: u8 = 0b10100001;
 
         let value = u16::from(cs) << 8;
         let index = u16::from(entity_id) << 8 | u16::from(interface);
 
-        self.with_usb(|usb| {
-            usb.read_control(
-                GET_ENTITY_REQ,
-                request as _,
-                value,
-                index,
-                buf,
-                self.timeout,
-            )
-            .during(Action::ReadingControl)?;
-            Ok(())
-        })
+        let bytes = self.with_usb(|usb| {
+            usb.read_control(GET_ENTITY_REQ, request as _, value, index, buf, timeout)
+                .during(Action::ReadingControl(ControlAddress {
+                    interface,
+                    entity: entity_id,
+                    selector: cs,
+                }))
+        })?;
+
+        if bytes != buf.len() {
+            log::warn!(
+                "{:?} of control {:#04x} on entity {} returned {} bytes, expected {}",
+                request,
+                cs,
+                entity_id,
+                bytes,
+                buf.len()
+            );
+
+            // Zero-extend short reads so that the high bytes of the value don't contain garbage.
+            buf[bytes..].iter_mut().for_each(|b| *b = 0);
+        }
+
+        Ok(bytes)
     }
 
     pub fn read_manufacturer_string(&self) -> Result<String> {
@@ -218,10 +513,200 @@ impl UvcDevice {
             .during(Action::ReadingDeviceString)?)
     }
 
+    /// Returns the underlying libusb device handle.
+    ///
+    /// This is an escape hatch for issuing requests this library does not support (for example,
+    /// vendor-specific requests, or requests to other functions of a composite device). Some care
+    /// is needed when using it:
+    ///
+    /// - The UVC interfaces are already claimed by this `UvcDevice`. Do not release them, or
+    ///   change the active configuration.
+    /// - Changing the alternate setting of a streaming interface, or performing requests on the
+    ///   streaming interfaces, can interfere with stream negotiation and active [`Stream`]s.
+    /// - Transfers issued through the handle do not benefit from the timeout workarounds this
+    ///   library applies.
+    ///
+    /// [`Stream`]: streaming_interface::Stream
+    pub fn usb_handle(&self) -> &DeviceHandle<Context> {
+        &self.usb
+    }
+
+    /// Performs a `GET_*` `request` on the control `selector` of the entity `entity_id`, returning
+    /// the number of Bytes the device returned.
+    ///
+    /// This allows accessing controls of entities this library does not model (eg. vendor-specific
+    /// terminals or extension units). `request` has to be one of the `GET_*` requests. If the
+    /// device returns fewer Bytes than `buf` can hold, the rest of `buf` is zeroed.
+    ///
+    /// Returns an error without contacting the device if `request` is not a `GET_*` request.
+    pub fn get_entity_control(
+        &self,
+        entity_id: u8,
+        selector: u8,
+        request: Request,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        if !request.is_get() {
+            return err(
+                format!("{:?} is not a GET request", request),
+                Action::ReadingControl(self.entity_control_address(entity_id, selector)),
+            );
+        }
+
+        self.read_entity(entity_id, request, selector, buf)
+    }
+
+    /// Queries the length of the control `selector` of the entity `entity_id` with a `GET_LEN`
+    /// request.
+    ///
+    /// Controls of extension units (and a few standard controls) have a device-defined length,
+    /// which is needed to size the buffer passed to [`UvcDevice::get_entity_control`]. Returns
+    /// an error if the device reports a length of 0.
+    pub fn read_control_len(&self, entity_id: u8, selector: u8) -> Result<u16> {
+        let mut buf = [0; 2];
+        self.read_entity(entity_id, Request::GetLen, selector, &mut buf)?;
+        match u16::from_le_bytes(buf) {
+            0 => err(
+                "device reported a control length of 0",
+                Action::ReadingControl(self.entity_control_address(entity_id, selector)),
+            ),
+            len => Ok(len),
+        }
+    }
+
+    /// Performs a `SET_CUR` request on the control `selector` of the entity `entity_id`.
+    ///
+    /// This is the counterpart of [`UvcDevice::get_entity_control`].
+    pub fn set_entity_control(&self, entity_id: u8, selector: u8, data: &[u8]) -> Result<()> {
+        self.set_entity(entity_id, selector, data)
+    }
+
+    /// Collects an overview of the device's identity and capabilities.
+    ///
+    /// This reads the manufacturer and product strings from the device, everything else is taken
+    /// from the descriptors.
+    pub fn summary(&self) -> Result<DeviceSummary> {
+        DeviceSummary::new(self)
+    }
+
+    /// Returns the speed the device is operating at.
+    ///
+    /// This determines the bandwidth available for streaming: uncompressed high-resolution
+    /// formats at high frame rates typically require a SuperSpeed link.
+    pub fn usb_speed(&self) -> Speed {
+        self.usb.device().speed()
+    }
+
+    /// Returns the timeout used for USB transfers to and from the device.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Sets the timeout used for USB transfers to and from the device (1 second by default).
+    ///
+    /// This applies to control requests and to reads from a [`Stream`]. A timeout of zero means
+    /// that transfers never time out.
+    ///
+    /// [`StreamingInterface`]s pick up the timeout when they are created, and can override it with
+    /// [`StreamingInterface::set_negotiation_timeout`].
+    ///
+    /// [`Stream`]: streaming_interface::Stream
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the UVC specification version the device implements.
+    pub fn uvc_version(&self) -> BcdVersion {
+        self.topology().header().uvc_version()
+    }
+
+    /// Returns the frequency of the device clock in Hz.
+    ///
+    /// This is the value to use when interpreting timestamps in payload headers. The frequency
+    /// in the Video Control interface header takes precedence. If that is 0, the
+    /// `dwClockFrequency` field of the first streaming interface's current probe state is used
+    /// instead (UVC 1.1 and later). If neither is available, an error is returned.
+    pub fn clock_frequency(&self) -> Result<u32> {
+        let freq = self.topology().header().clock_frequency();
+        if freq != 0 {
+            return Ok(freq);
+        }
+
+        if let Some(desc) = self.default_streaming_interface() {
+            let interface = self.streaming_interface_by_id(desc.id());
+            if let Some(freq) = interface.probe_clock_frequency()? {
+                if freq != 0 {
+                    return Ok(freq);
+                }
+            }
+        }
+
+        err(
+            "device does not report its clock frequency",
+            Action::StreamNegotiation,
+        )
+    }
+
+    /// Returns the address of the Video Control interface's interrupt endpoint, which the device
+    /// uses to report status changes.
+    ///
+    /// Returns `None` if the device has no such endpoint.
+    pub fn status_endpoint_address(&self) -> Option<u8> {
+        self.uvc_info.control_interface.control_interrupt_ep
+    }
+
+    /// Waits up to `timeout` for a status packet on the Video Control interface's interrupt
+    /// endpoint, and returns its raw contents.
+    ///
+    /// Status packets report control changes (eg. a control whose value changed autonomously, or
+    /// the completion of an asynchronous control request) and button presses. The buffer is sized
+    /// by the `wMaxTransferSize` the device declares for the endpoint.
+    ///
+    /// Returns an error if the device has no interrupt endpoint, or if no status packet arrives
+    /// before `timeout` elapses.
+    pub fn read_status(&self, timeout: Duration) -> Result<Vec<u8>> {
+        // Status packets are at most a few Bytes plus a control value, so this suffices for
+        // devices that leave out the class-specific endpoint descriptor.
+        const DEFAULT_STATUS_BUFFER_SIZE: usize = 64;
+
+        let control = &self.uvc_info.control_interface;
+        let endpoint = match control.control_interrupt_ep {
+            Some(endpoint) => endpoint,
+            None => {
+                return err(
+                    "Video Control interface has no interrupt endpoint",
+                    Action::ReadingStatus,
+                )
+            }
+        };
+        let size = control
+            .interrupt_max_transfer_size
+            .map_or(DEFAULT_STATUS_BUFFER_SIZE, usize::from);
+
+        let mut buf = vec![0; size];
+        let len = self
+            .usb
+            .read_interrupt(endpoint, &mut buf, timeout)
+            .during(Action::ReadingStatus)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    /// Returns whether the device implements UVC 1.5 (or later).
+    pub fn supports_uvc_1_5(&self) -> bool {
+        self.uvc_version() >= BcdVersion::UVC_1_5
+    }
+
     pub fn topology(&self) -> &Topology {
         &self.uvc_info.control_interface.topo
     }
 
+    /// Returns the spec violations that were found (and worked around) while parsing the device's
+    /// descriptors.
+    pub fn descriptor_warnings(&self) -> &[ParseWarning] {
+        &self.uvc_info.warnings
+    }
+
     /// Returns the device's streaming interfaces.
     ///
     /// Streaming interfaces transport video data over the USB channel (either from the device to
@@ -230,10 +715,113 @@ impl UvcDevice {
         &self.uvc_info.streaming_interfaces
     }
 
+    /// Returns the device's first streaming interface.
+    ///
+    /// Most devices only have a single streaming interface. Returns `None` if the device has no
+    /// streaming interfaces, in which case it can still be used to access controls.
+    pub fn default_streaming_interface(&self) -> Option<&StreamingInterfaceDesc> {
+        self.streaming_interfaces().first()
+    }
+
+    /// Returns whether a [`Stream`] is currently running on any of the device's streaming
+    /// interfaces.
+    ///
+    /// [`Stream`]: streaming_interface::Stream
+    pub fn is_streaming(&self) -> bool {
+        !self.active_streams.lock().unwrap().is_empty()
+    }
+
+    /// Returns the streaming interface a [`Stream`] is currently running on.
+    ///
+    /// If streams are running on several interfaces, the one that was started first is returned.
+    ///
+    /// [`Stream`]: streaming_interface::Stream
+    pub fn active_streaming_interface(&self) -> Option<StreamingInterfaceId> {
+        self.active_streams
+            .lock()
+            .unwrap()
+            .first()
+            .map(|&id| StreamingInterfaceId(id))
+    }
+
+    /// Registers a new stream on the interface `id`, failing if one is already running there.
+    pub(crate) fn stream_started(&self, id: StreamingInterfaceId) -> Result<()> {
+        let mut active = self.active_streams.lock().unwrap();
+        if active.contains(&id.0) {
+            return err(
+                format!(
+                    "a stream is already running on streaming interface {}",
+                    id.0
+                ),
+                Action::StartingStream,
+            );
+        }
+        active.push(id.0);
+        Ok(())
+    }
+
+    pub(crate) fn stream_stopped(&self, id: StreamingInterfaceId) {
+        let mut active = self.active_streams.lock().unwrap();
+        if let Some(pos) = active.iter().position(|&i| i == id.0) {
+            active.remove(pos);
+        }
+    }
+
+    /// Selects the alternate setting `alt` of the streaming interface `id`.
+    pub(crate) fn set_alt_setting(&self, id: StreamingInterfaceId, alt: u8) -> rusb::Result<()> {
+        self.usb.set_alternate_setting(id.0, alt)?;
+        let mut settings = self.alt_settings.lock().unwrap();
+        settings.retain(|&(interface, _)| interface != id.0);
+        if alt != 0 {
+            settings.push((id.0, alt));
+        }
+        Ok(())
+    }
+
+    /// Returns the alternate setting currently selected on the streaming interface `id`.
+    pub(crate) fn alt_setting(&self, id: StreamingInterfaceId) -> u8 {
+        self.alt_settings
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|&&(interface, _)| interface == id.0)
+            .map_or(0, |&(_, alt)| alt)
+    }
+
+    /// Returns the sorted, de-duplicated `(width, height)` pairs supported by any frame of any
+    /// streaming interface.
+    ///
+    /// Use [`StreamingInterfaceDesc::supported_resolutions`] to restrict this to one interface.
+    pub fn supported_resolutions(&self) -> Vec<(u16, u16)> {
+        let mut resolutions = self
+            .streaming_interfaces()
+            .iter()
+            .flat_map(StreamingInterfaceDesc::supported_resolutions)
+            .collect::<Vec<_>>();
+        resolutions.sort_unstable();
+        resolutions.dedup();
+        resolutions
+    }
+
+    /// Grants access to the streaming interface with the given ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the device has no streaming interface with ID `id`. Use
+    /// [`UvcDevice::try_streaming_interface_by_id`] if `id` may come from a different device.
     pub fn streaming_interface_by_id(&self, id: StreamingInterfaceId) -> StreamingInterface<'_> {
         StreamingInterface::new(self, id)
     }
 
+    /// Grants access to the streaming interface with the given ID, or returns `None` if the
+    /// device has no such interface.
+    pub fn try_streaming_interface_by_id(
+        &self,
+        id: StreamingInterfaceId,
+    ) -> Option<StreamingInterface<'_>> {
+        StreamingInterface::try_new(self, id)
+    }
+
     pub fn camera_terminal_by_id(&self, id: CameraId) -> CameraTerminal<'_> {
         CameraTerminal::new(self, id)
     }
@@ -241,24 +829,34 @@ impl UvcDevice {
     pub fn processing_unit_by_id(&self, id: ProcessingUnitId) -> ProcessingUnit<'_> {
         ProcessingUnit::new(self, id)
     }
+
+    pub fn extension_unit_by_id(&self, id: ExtensionUnitId) -> ExtensionUnit<'_> {
+        ExtensionUnit::new(self, id)
+    }
+
+    pub fn selector_unit_by_id(&self, id: SelectorUnitId) -> SelectorUnit<'_> {
+        SelectorUnit::new(self, id)
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
-#[allow(dead_code)]
-enum Request {
-    Undefined = 0x00,
-    SetCur = 0x01,
-    SetCurAll = 0x11,
-    GetCur = 0x81,
-    GetMin = 0x82,
-    GetMax = 0x83,
-    GetRes = 0x84,
-    GetLen = 0x85,
-    GetInfo = 0x86,
-    GetDef = 0x87,
-    GetCurAll = 0x91,
-    GetMinAll = 0x92,
-    GetMaxAll = 0x93,
-    GetResAll = 0x94,
-    GetDefAll = 0x97,
+// Make sure that the types stay thread-safe.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<UvcDeviceDesc>();
+    assert_send_sync::<UvcDevice>();
+};
+
+impl Drop for UvcDevice {
+    fn drop(&mut self) {
+        // If the device was opened with `OpenOptions::auto_detach_kernel_driver` enabled (the
+        // default), libusb will reattach the kernel driver (eg. `uvcvideo` on Linux) when the
+        // interface is released.
+        let control = self.uvc_info.control_interface.interface_number;
+        let streaming = self.uvc_info.streaming_interfaces.iter().map(|i| i.id().0);
+        for interface in std::iter::once(control).chain(streaming) {
+            if let Err(e) = self.usb.release_interface(interface) {
+                log::warn!("failed to release interface {}: {}", interface, e);
+            }
+        }
+    }
 }
@@ -0,0 +1,169 @@
+//! Parsing of the headers that precede the data of each UVC payload.
+
+use std::convert::TryInto;
+
+use bitflags::bitflags;
+
+use crate::{
+    error::{err, Action},
+    Result,
+};
+
+bitflags! {
+    /// The `bmHeaderInfo` field of a payload header.
+    pub struct PayloadHeaderFlags: u8 {
+        /// Frame ID, toggles at the start of every video frame.
+        const FRAME_ID = 1 << 0;
+        /// The payload contains the end of a video frame.
+        const END_OF_FRAME = 1 << 1;
+        /// The header contains a presentation time stamp.
+        const PRESENTATION_TIME = 1 << 2;
+        /// The header contains a source clock reference.
+        const SOURCE_CLOCK = 1 << 3;
+        /// The payload belongs to a still image rather than a video frame.
+        const STILL_IMAGE = 1 << 5;
+        /// The device encountered an error while streaming this payload.
+        ///
+        /// The `StreamErrorCode` control of the streaming interface describes the error.
+        const ERROR = 1 << 6;
+        /// This is the last header field (always set for single-header payloads).
+        const END_OF_HEADER = 1 << 7;
+    }
+}
+
+/// The header at the start of every UVC payload.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadHeader {
+    len: u8,
+    flags: PayloadHeaderFlags,
+    pts: Option<u32>,
+    scr: Option<(u32, u16)>,
+}
+
+impl PayloadHeader {
+    /// Parses the header at the start of `payload`, returning it along with the payload data that
+    /// follows it.
+    ///
+    /// Returns an error if `bHeaderLength` is too small for the fields indicated by
+    /// `bmHeaderInfo`, or larger than `payload`.
+    pub fn parse(payload: &[u8]) -> Result<(PayloadHeader, &[u8])> {
+        let (len, flags) = match payload {
+            [len, flags, ..] => (*len, PayloadHeaderFlags::from_bits_truncate(*flags)),
+            _ => {
+                return err(
+                    format!(
+                        "payload of {} Bytes is too short to contain a payload header",
+                        payload.len()
+                    ),
+                    Action::ParsingPayloadHeader,
+                )
+            }
+        };
+        if len < 2 {
+            return err(
+                format!("`bHeaderLength` is {}, but must be at least 2", len),
+                Action::ParsingPayloadHeader,
+            );
+        }
+        if usize::from(len) > payload.len() {
+            return err(
+                format!(
+                    "`bHeaderLength` is {}, but the payload is only {} Bytes long",
+                    len,
+                    payload.len()
+                ),
+                Action::ParsingPayloadHeader,
+            );
+        }
+
+        let mut fields = &payload[2..usize::from(len)];
+        let mut take = |n: usize, field: &str| match fields.get(..n) {
+            Some(bytes) => {
+                fields = &fields[n..];
+                Ok(bytes)
+            }
+            None => err(
+                format!(
+                    "`bHeaderLength` is {}, which is too short to contain {}",
+                    len, field
+                ),
+                Action::ParsingPayloadHeader,
+            ),
+        };
+
+        let pts = if flags.contains(PayloadHeaderFlags::PRESENTATION_TIME) {
+            // unwrap: `take` returns exactly the requested number of Bytes
+            Some(u32::from_le_bytes(
+                take(4, "`dwPresentationTime`")?.try_into().unwrap(),
+            ))
+        } else {
+            None
+        };
+        let scr = if flags.contains(PayloadHeaderFlags::SOURCE_CLOCK) {
+            let scr = take(6, "`scrSourceClock`")?;
+            Some((
+                u32::from_le_bytes(scr[..4].try_into().unwrap()),
+                u16::from_le_bytes(scr[4..].try_into().unwrap()) & 0x7ff,
+            ))
+        } else {
+            None
+        };
+
+        let header = PayloadHeader {
+            len,
+            flags,
+            pts,
+            scr,
+        };
+        Ok((header, &payload[usize::from(len)..]))
+    }
+
+    /// Returns the length of the header in Bytes (`bHeaderLength`).
+    pub fn header_len(&self) -> u8 {
+        self.len
+    }
+
+    pub fn flags(&self) -> PayloadHeaderFlags {
+        self.flags
+    }
+
+    /// Returns the value of the frame ID bit, which toggles at the start of every video frame.
+    pub fn frame_id(&self) -> bool {
+        self.flags.contains(PayloadHeaderFlags::FRAME_ID)
+    }
+
+    /// Returns whether this payload contains the end of a video frame.
+    ///
+    /// Not all devices set this bit, so a change of the [`frame_id`] has to be treated as the end
+    /// of a frame as well.
+    ///
+    /// [`frame_id`]: PayloadHeader::frame_id
+    pub fn is_end_of_frame(&self) -> bool {
+        self.flags.contains(PayloadHeaderFlags::END_OF_FRAME)
+    }
+
+    /// Returns whether this payload belongs to a still image.
+    pub fn is_still_image(&self) -> bool {
+        self.flags.contains(PayloadHeaderFlags::STILL_IMAGE)
+    }
+
+    /// Returns whether the device reported an error for this payload.
+    pub fn is_error(&self) -> bool {
+        self.flags.contains(PayloadHeaderFlags::ERROR)
+    }
+
+    /// Returns the presentation time stamp (`dwPresentationTime`), in units of the device clock.
+    ///
+    /// The device clock frequency is returned by [`UvcDevice::clock_frequency`].
+    ///
+    /// [`UvcDevice::clock_frequency`]: crate::UvcDevice::clock_frequency
+    pub fn presentation_time(&self) -> Option<u32> {
+        self.pts
+    }
+
+    /// Returns the source clock reference as the device clock time stamp and the 11-bit USB
+    /// start-of-frame token counter at which it was sampled.
+    pub fn source_clock(&self) -> Option<(u32, u16)> {
+        self.scr
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
-    control::ControlValue,
-    topo::{ProcessingUnitDesc, ProcessingUnitId},
-    Request, Result, UvcDevice,
+    control::{ControlDescriptor, ControlInfo, ControlRange, ControlValue, ControlValueDyn},
+    request::Request,
+    topo::{ProcessingUnitControls, ProcessingUnitDesc, ProcessingUnitId},
+    Result, UvcDevice,
 };
 
 /// Grants access to a processing unit.
@@ -17,6 +18,114 @@ impl<'a> ProcessingUnit<'a> {
         Self { device, desc }
     }
 
+    /// Returns the controls supported by this processing unit.
+    ///
+    /// This is derived from the `bmControls` bitmap in the unit's descriptor.
+    pub fn supported_controls(&self) -> Vec<ControlId> {
+        let controls = self.desc.controls();
+        CONTROL_BITS
+            .iter()
+            .filter(|(bit, _)| controls.contains(*bit))
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
+    /// Returns the descriptors of the controls supported by this processing unit.
+    ///
+    /// Together with the `*_dyn` methods, this allows accessing controls whose selectors are only
+    /// known at runtime.
+    pub fn control_descriptors(&self) -> Vec<ControlDescriptor<ControlId>> {
+        self.supported_controls()
+            .into_iter()
+            .map(ControlId::descriptor)
+            .collect()
+    }
+
+    /// Returns an iterator that reads the current value and range of every supported control.
+    ///
+    /// Each item contains the control's name, its current value, and its range. An item is only
+    /// an error if reading the current value fails. Range values the device rejects are left out
+    /// of the [`ControlRange`], since many controls don't support all of them.
+    pub fn iter_controls(
+        &self,
+    ) -> impl Iterator<Item = Result<(&'static str, ControlValueDyn, ControlRange)>> + '_ {
+        self.supported_controls().into_iter().map(move |id| {
+            let current = id.decode_dyn(self.read_control_dyn(id)?);
+            let read = |req| match self.read_control_vec(id, req) {
+                Ok(value) => Some(id.decode_dyn(value)),
+                Err(e) => {
+                    log::debug!("{:?} of {} failed: {}", req, id.name(), e);
+                    None
+                }
+            };
+            let range = ControlRange::new(
+                read(Request::GetMin),
+                read(Request::GetMax),
+                read(Request::GetRes),
+                read(Request::GetDef),
+            );
+            Ok((id.name(), current, range))
+        })
+    }
+
+    /// Reads the current value of the control `id` without decoding it.
+    pub fn read_control_dyn(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetCur)
+    }
+
+    /// Reads the minimum value of the control `id` without decoding it.
+    pub fn read_control_dyn_min(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetMin)
+    }
+
+    /// Reads the maximum value of the control `id` without decoding it.
+    pub fn read_control_dyn_max(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetMax)
+    }
+
+    /// Reads the resolution of the control `id` without decoding it.
+    pub fn read_control_dyn_res(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetRes)
+    }
+
+    /// Reads the default value of the control `id` without decoding it.
+    pub fn read_control_dyn_default(&self, id: ControlId) -> Result<Vec<u8>> {
+        self.read_control_vec(id, Request::GetDef)
+    }
+
+    /// Sets the control `id` to the raw `value`.
+    ///
+    /// `value` should be [`ControlId::value_len`] Bytes long.
+    pub fn set_control_dyn(&mut self, id: ControlId, value: &[u8]) -> Result<()> {
+        self.set_control_raw(id, value)
+    }
+
+    fn read_control_vec(&self, id: ControlId, req: Request) -> Result<Vec<u8>> {
+        let mut buf = vec![0; id.value_len()];
+        self.read_control_raw(id, req, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reads the current value of the control `C` without decoding it.
+    ///
+    /// This is useful for telling apart decoding bugs from devices returning unexpected values.
+    pub fn read_control_raw_vec<C: ProcessingUnitControl>(&self) -> Result<Vec<u8>> {
+        self.read_control_vec(C::ID, Request::GetCur)
+    }
+
+    /// Reads the capabilities and state of the control `C` with a `GET_INFO` request.
+    pub fn read_control_info<C: ProcessingUnitControl>(&self) -> Result<ControlInfo> {
+        let mut buf = [0; 1];
+        self.read_control_raw(C::ID, Request::GetInfo, &mut buf)?;
+        Ok(ControlInfo::from_bits_truncate(buf[0]))
+    }
+
+    /// Queries the length of the control `C` in Bytes with a `GET_LEN` request.
+    pub fn read_control_len<C: ProcessingUnitControl>(&self) -> Result<u16> {
+        self.device
+            .read_control_len(self.desc.id().as_raw(), C::ID as _)
+    }
+
     pub fn read_control<C: ProcessingUnitControl>(&self) -> Result<C::Value> {
         let mut buf = <<C::Value as ControlValue>::Buf>::default();
         self.read_control_raw(C::ID, Request::GetCur, buf.as_mut())?;
@@ -53,18 +162,33 @@ impl<'a> ProcessingUnit<'a> {
         self.set_control_raw(C::ID, buf.as_mut())
     }
 
+    /// Resets a control to its default value.
+    ///
+    /// This reads the default value with `GET_DEF` and writes it back with `SET_CUR`, without
+    /// decoding it.
+    pub fn reset_to_default<C: ProcessingUnitControl>(&mut self) -> Result<()> {
+        let mut buf = <<C::Value as ControlValue>::Buf>::default();
+        self.read_control_raw(C::ID, Request::GetDef, buf.as_mut())?;
+        self.set_control_raw(C::ID, buf.as_mut())
+    }
+
     fn set_control_raw(&mut self, control: ControlId, value: &[u8]) -> Result<()> {
         self.device
             .set_entity(self.desc.id().as_raw(), control as _, value)
     }
 
-    fn read_control_raw(&self, control: ControlId, request: Request, buf: &mut [u8]) -> Result<()> {
+    fn read_control_raw(
+        &self,
+        control: ControlId,
+        request: Request,
+        buf: &mut [u8],
+    ) -> Result<usize> {
         self.device
             .read_entity(self.desc.id().as_raw(), request, control as _, buf)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ControlId {
     #[allow(dead_code)]
@@ -90,6 +214,147 @@ pub enum ControlId {
     ContrastAuto = 0x13,
 }
 
+impl ControlId {
+    /// Returns the length of the control's value in Bytes, as specified by UVC.
+    ///
+    /// Returns 0 for [`ControlId::Undefined`].
+    pub fn value_len(self) -> usize {
+        match self {
+            ControlId::Undefined => 0,
+            ControlId::BacklightCompensation => 2,
+            ControlId::Brightness => 2,
+            ControlId::Contrast => 2,
+            ControlId::Gain => 2,
+            ControlId::PowerLineFrequency => 1,
+            ControlId::Hue => 2,
+            ControlId::Saturation => 2,
+            ControlId::Sharpness => 2,
+            ControlId::Gamma => 2,
+            ControlId::WhiteBalanceTemperature => 2,
+            ControlId::WhiteBalanceTemperatureAuto => 1,
+            ControlId::WhiteBalanceComponent => 4,
+            ControlId::WhiteBalanceComponentAuto => 1,
+            ControlId::DigitalMultiplier => 2,
+            ControlId::DigitalMultiplierLimit => 2,
+            ControlId::HueAuto => 1,
+            ControlId::AnalogVideoStandard => 1,
+            ControlId::AnalogVideoLockStatus => 1,
+            ControlId::ContrastAuto => 1,
+        }
+    }
+
+    /// Returns the control's name, as used in the UVC specification.
+    pub fn name(self) -> &'static str {
+        match self {
+            ControlId::Undefined => "Undefined",
+            ControlId::BacklightCompensation => "Backlight Compensation",
+            ControlId::Brightness => "Brightness",
+            ControlId::Contrast => "Contrast",
+            ControlId::Gain => "Gain",
+            ControlId::PowerLineFrequency => "Power Line Frequency",
+            ControlId::Hue => "Hue",
+            ControlId::Saturation => "Saturation",
+            ControlId::Sharpness => "Sharpness",
+            ControlId::Gamma => "Gamma",
+            ControlId::WhiteBalanceTemperature => "White Balance Temperature",
+            ControlId::WhiteBalanceTemperatureAuto => "White Balance Temperature, Auto",
+            ControlId::WhiteBalanceComponent => "White Balance Component",
+            ControlId::WhiteBalanceComponentAuto => "White Balance Component, Auto",
+            ControlId::DigitalMultiplier => "Digital Multiplier",
+            ControlId::DigitalMultiplierLimit => "Digital Multiplier Limit",
+            ControlId::HueAuto => "Hue, Auto",
+            ControlId::AnalogVideoStandard => "Analog Video Standard",
+            ControlId::AnalogVideoLockStatus => "Analog Lock Status",
+            ControlId::ContrastAuto => "Contrast, Auto",
+        }
+    }
+
+    /// Decodes a raw value of this control into the matching [`ControlValueDyn`] variant.
+    fn decode_dyn(self, raw: Vec<u8>) -> ControlValueDyn {
+        match self {
+            ControlId::Brightness | ControlId::Hue => ControlValueDyn::I16(i16::decode(&raw)),
+            ControlId::BacklightCompensation
+            | ControlId::Contrast
+            | ControlId::Gain
+            | ControlId::Saturation
+            | ControlId::Sharpness
+            | ControlId::Gamma
+            | ControlId::WhiteBalanceTemperature
+            | ControlId::DigitalMultiplier
+            | ControlId::DigitalMultiplierLimit => ControlValueDyn::U16(u16::decode(&raw)),
+            ControlId::PowerLineFrequency
+            | ControlId::WhiteBalanceTemperatureAuto
+            | ControlId::WhiteBalanceComponentAuto
+            | ControlId::HueAuto
+            | ControlId::AnalogVideoStandard
+            | ControlId::AnalogVideoLockStatus
+            | ControlId::ContrastAuto => ControlValueDyn::U8(u8::decode(&raw)),
+            _ => ControlValueDyn::Raw(raw),
+        }
+    }
+
+    /// Returns the static information about this control.
+    pub fn descriptor(self) -> ControlDescriptor<ControlId> {
+        ControlDescriptor::new(self, self.value_len(), self.name())
+    }
+}
+
+/// Maps the bits of the processing unit's `bmControls` to the corresponding control selectors.
+const CONTROL_BITS: &[(ProcessingUnitControls, ControlId)] = &[
+    (ProcessingUnitControls::BRIGHTNESS, ControlId::Brightness),
+    (ProcessingUnitControls::CONTRAST, ControlId::Contrast),
+    (ProcessingUnitControls::HUE, ControlId::Hue),
+    (ProcessingUnitControls::SATURATION, ControlId::Saturation),
+    (ProcessingUnitControls::SHARPNESS, ControlId::Sharpness),
+    (ProcessingUnitControls::GAMMA, ControlId::Gamma),
+    (
+        ProcessingUnitControls::WHITE_BALANCE_TEMPERATURE,
+        ControlId::WhiteBalanceTemperature,
+    ),
+    (
+        ProcessingUnitControls::WHITE_BALANCE_COMPONENT,
+        ControlId::WhiteBalanceComponent,
+    ),
+    (
+        ProcessingUnitControls::BACKLIGHT_COMPENSATION,
+        ControlId::BacklightCompensation,
+    ),
+    (ProcessingUnitControls::GAIN, ControlId::Gain),
+    (
+        ProcessingUnitControls::POWER_LINE_FREQUENCY,
+        ControlId::PowerLineFrequency,
+    ),
+    (ProcessingUnitControls::HUE_AUTO, ControlId::HueAuto),
+    (
+        ProcessingUnitControls::WHITE_BALANCE_TEMPERATURE_AUTO,
+        ControlId::WhiteBalanceTemperatureAuto,
+    ),
+    (
+        ProcessingUnitControls::WHITE_BALANCE_COMPONENT_AUTO,
+        ControlId::WhiteBalanceComponentAuto,
+    ),
+    (
+        ProcessingUnitControls::DIGITAL_MULTIPLIER,
+        ControlId::DigitalMultiplier,
+    ),
+    (
+        ProcessingUnitControls::DIGITAL_MULTIPLIER_LIMIT,
+        ControlId::DigitalMultiplierLimit,
+    ),
+    (
+        ProcessingUnitControls::ANALOG_VIDEO_STANDARD,
+        ControlId::AnalogVideoStandard,
+    ),
+    (
+        ProcessingUnitControls::ANALOG_VIDEO_LOCK_STATUS,
+        ControlId::AnalogVideoLockStatus,
+    ),
+    (
+        ProcessingUnitControls::CONTRAST_AUTO,
+        ControlId::ContrastAuto,
+    ),
+];
+
 pub trait ProcessingUnitControl {
     // TODO seal
     type Value: ControlValue;
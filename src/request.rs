@@ -0,0 +1,28 @@
+/// Class-specific request codes (A.8).
+#[derive(Debug, Clone, Copy)]
+#[allow(dead_code)]
+pub enum Request {
+    Undefined = 0x00,
+    SetCur = 0x01,
+    SetCurAll = 0x11,
+    GetCur = 0x81,
+    GetMin = 0x82,
+    GetMax = 0x83,
+    GetRes = 0x84,
+    GetLen = 0x85,
+    GetInfo = 0x86,
+    GetDef = 0x87,
+    GetCurAll = 0x91,
+    GetMinAll = 0x92,
+    GetMaxAll = 0x93,
+    GetResAll = 0x94,
+    GetDefAll = 0x97,
+}
+
+impl Request {
+    /// Returns whether this is one of the `GET_*` requests, which transfer data from the device to
+    /// the host.
+    pub fn is_get(self) -> bool {
+        self as u8 & 0x80 != 0
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    error::{err, Action},
+    request::Request,
+    topo::{SelectorUnitDesc, SelectorUnitId},
+    Result, UvcDevice,
+};
+
+/// Selector for the `SU_INPUT_SELECT_CONTROL`, the only control of selector units.
+const INPUT_SELECT_CONTROL: u8 = 0x01;
+
+/// Grants access to a selector unit.
+///
+/// A selector unit routes one of its inputs to its output. Inputs are identified by their 1-based
+/// index in [`SelectorUnitDesc::inputs`].
+pub struct SelectorUnit<'a> {
+    device: &'a UvcDevice,
+    desc: &'a SelectorUnitDesc,
+}
+
+impl<'a> SelectorUnit<'a> {
+    pub(crate) fn new(device: &'a UvcDevice, id: SelectorUnitId) -> Self {
+        let desc = device.topology().selector_unit_by_id(id);
+
+        Self { device, desc }
+    }
+
+    pub fn desc(&self) -> &'a SelectorUnitDesc {
+        self.desc
+    }
+
+    /// Returns the 1-based index of the currently selected input.
+    pub fn get_selected_input(&self) -> Result<u8> {
+        let mut buf = [0; 1];
+        self.device.read_entity(
+            self.desc.id().as_raw(),
+            Request::GetCur,
+            INPUT_SELECT_CONTROL,
+            &mut buf,
+        )?;
+        Ok(buf[0])
+    }
+
+    /// Selects the input with the 1-based `index`.
+    ///
+    /// Returns an error without contacting the device if the unit has no input with that index.
+    pub fn select_input(&mut self, index: u8) -> Result<()> {
+        let id = self.desc.id().as_raw();
+        let num_inputs = self.desc.inputs().len();
+        if index == 0 || usize::from(index) > num_inputs {
+            return err(
+                format!(
+                    "input index {} is out of range, selector unit {} has {} inputs",
+                    index,
+                    self.desc.id(),
+                    num_inputs
+                ),
+                Action::WritingControl(
+                    self.device.entity_control_address(id, INPUT_SELECT_CONTROL),
+                ),
+            );
+        }
+
+        self.device.set_entity(id, INPUT_SELECT_CONTROL, &[index])
+    }
+}
@@ -1,69 +1,504 @@
 use std::{
+    fmt,
     io::{self, Read},
-    time::Duration,
+    mem,
+    ops::Range,
+    time::{Duration, Instant},
 };
 
+use rusb::Speed;
+
 use crate::{
-    control::{ControlValue, ProbeCommitControls},
-    error::{Action, ResultExt},
-    topo::{FormatIndex, FrameIndex, StreamingInterfaceDesc, StreamingInterfaceId},
-    Request, Result, UvcDevice,
+    control::{
+        probe_commit_len, ControlValue, ProbeCommitControls, ProbeHint, StillProbeCommitControls,
+        PROBE_COMMIT_LEN_MAX,
+    },
+    error::{err, Action, ResultExt},
+    formats::PixelFormat,
+    iso::IsoTransfers,
+    payload::PayloadHeader,
+    request::Request,
+    topo::{
+        AltSetting, FormatIndex, Frame, FrameIndex, InputInterfaceInfo, PerFormatControls,
+        StillCaptureMethod, StreamingInterfaceDesc, StreamingInterfaceId, SupportedFrameIntervals,
+    },
+    util::{duration_from_100ns, duration_to_100ns},
+    Result, UvcDevice,
 };
 
 pub struct StreamingInterface<'a> {
     device: &'a UvcDevice,
     desc: &'a StreamingInterfaceDesc,
+    encoding: EncodingParams,
+    /// Timeout for this interface's control requests. Defaults to the device's timeout.
+    negotiation_timeout: Duration,
+}
+
+/// Encoder parameters requested by the user, included in the next stream negotiation.
+#[derive(Default, Clone)]
+struct EncodingParams {
+    comp_quality: Option<u16>,
+    key_frame_rate: Option<u16>,
+    p_frame_rate: Option<u16>,
 }
 
 impl<'a> StreamingInterface<'a> {
     pub(crate) fn new(device: &'a UvcDevice, id: StreamingInterfaceId) -> Self {
+        Self::try_new(device, id).unwrap()
+    }
+
+    pub(crate) fn try_new(device: &'a UvcDevice, id: StreamingInterfaceId) -> Option<Self> {
         let desc = device
             .streaming_interfaces()
             .iter()
-            .find(|i| i.id().0 == id.0)
-            .unwrap();
+            .find(|i| i.id().0 == id.0)?;
+
+        Some(Self {
+            device,
+            desc,
+            encoding: EncodingParams::default(),
+            negotiation_timeout: device.timeout,
+        })
+    }
+
+    /// Sets the compression quality to request when the stream is negotiated.
+    ///
+    /// `quality` ranges from 1 (lowest quality, highest compression) to 10000 (highest quality,
+    /// lowest compression). Stream negotiation will fail if the selected format does not support
+    /// adjusting the compression quality.
+    pub fn set_compression_quality(&mut self, quality: u16) {
+        self.encoding.comp_quality = Some(quality);
+    }
+
+    /// Sets the key frame rate to request when the stream is negotiated.
+    ///
+    /// `rate` is the number of frames between key frames. Stream negotiation will fail if the
+    /// selected format does not support adjusting the key frame rate.
+    pub fn set_key_frame_rate(&mut self, rate: u16) {
+        self.encoding.key_frame_rate = Some(rate);
+    }
+
+    /// Sets the P-frame rate to request when the stream is negotiated.
+    ///
+    /// `rate` is the number of P-frames between key frames. Stream negotiation will fail if the
+    /// selected format does not support adjusting the P-frame rate.
+    pub fn set_p_frame_rate(&mut self, rate: u16) {
+        self.encoding.p_frame_rate = Some(rate);
+    }
 
-        Self { device, desc }
+    /// Sets the timeout for the control requests of this interface, including the requests
+    /// performed during stream negotiation.
+    ///
+    /// Some devices take several seconds to accept a new format, and don't respond to requests in
+    /// the meantime. This timeout is independent of the one used when reading from a [`Stream`],
+    /// so it can be raised without making stream reads wait longer.
+    pub fn set_negotiation_timeout(&mut self, timeout: Duration) {
+        self.negotiation_timeout = timeout;
     }
 
-    pub fn start_stream(&mut self, format: FormatIndex, frame: FrameIndex) -> Result<Stream<'_>> {
-        self.negotiate_stream_params(format, frame)?;
-        Ok(self.start_stream_no_negotiate())
+    /// Negotiates stream parameters and starts streaming.
+    ///
+    /// The returned [`Stream`] only borrows the [`UvcDevice`], not this `StreamingInterface`, so
+    /// other entities of the device (eg. a [`CameraTerminal`][crate::camera::CameraTerminal]) can
+    /// still be accessed while the stream is running, including from another thread.
+    ///
+    /// Only one stream can run on an interface at a time. An error is returned if a [`Stream`] on
+    /// this interface is still alive.
+    ///
+    /// The returned stream reads from the interface's endpoint with bulk transfers. Use
+    /// [`StreamingInterface::start_iso_stream`] for interfaces with an isochronous endpoint.
+    pub fn start_stream(&mut self, format: FormatIndex, frame: FrameIndex) -> Result<Stream<'a>> {
+        let interval = self.find_frame(format, frame)?.default_frame_interval();
+        let stream = self.start_negotiated(format, frame, interval)?;
+        stream.check_bulk_bandwidth();
+        Ok(stream)
     }
 
-    pub fn start_stream_no_negotiate(&mut self) -> Stream<'_> {
-        Stream {
+    /// Negotiates stream parameters and starts streaming over an isochronous endpoint.
+    ///
+    /// Isochronous interfaces reserve bus bandwidth by switching to one of their alternate
+    /// settings. This selects the alternate setting with the smallest endpoint that can carry the
+    /// `dwMaxPayloadTransferSize` granted during negotiation, and switches back to alternate
+    /// setting 0 when the [`Stream`] is dropped.
+    ///
+    /// Each read from the returned stream yields the data of a single isochronous packet, which
+    /// is one UVC payload. The buffer passed to [`Read::read`] has to be large enough to hold a
+    /// whole payload, otherwise the read fails and the payload is discarded.
+    ///
+    /// The transfers are sized with the defaults of [`IsoStreamConfig`]. Use
+    /// [`StreamingInterface::start_iso_stream_with`] to configure them.
+    pub fn start_iso_stream(
+        &mut self,
+        format: FormatIndex,
+        frame: FrameIndex,
+    ) -> Result<Stream<'a>> {
+        self.start_iso_stream_with(format, frame, &IsoStreamConfig::new())
+    }
+
+    /// Like [`StreamingInterface::start_iso_stream`], but sizes the isochronous transfers
+    /// according to `config`.
+    pub fn start_iso_stream_with(
+        &mut self,
+        format: FormatIndex,
+        frame: FrameIndex,
+        config: &IsoStreamConfig,
+    ) -> Result<Stream<'a>> {
+        let interval = self.find_frame(format, frame)?.default_frame_interval();
+        let mut stream = self.start_negotiated(format, frame, interval)?;
+        stream.iso_config = *config;
+        stream.start_iso()?;
+        Ok(stream)
+    }
+
+    /// Negotiates a stream of the uncompressed `pixel_format` at `width` x `height` pixels and
+    /// `fps` frames per second, and starts it.
+    ///
+    /// This looks up the format with [`StreamingInterfaceDesc::format_by_pixel_format`] and its
+    /// frame with the requested resolution, and requests the supported frame interval closest to
+    /// `fps`. The device may still adjust the frame interval, [`Stream::frame_interval`] returns
+    /// the one that was committed.
+    ///
+    /// Interfaces with several alternate settings are streamed from like with
+    /// [`StreamingInterface::start_iso_stream`], all others like with
+    /// [`StreamingInterface::start_stream`].
+    pub fn start_stream_format(
+        &mut self,
+        pixel_format: PixelFormat,
+        width: u16,
+        height: u16,
+        fps: f64,
+    ) -> Result<Stream<'a>> {
+        let format = match self.desc.format_by_pixel_format(pixel_format) {
+            Some(format) => format.index(),
+            None => {
+                return err(
+                    format!(
+                        "streaming interface {} does not support the pixel format {:?}",
+                        self.desc.id(),
+                        pixel_format
+                    ),
+                    Action::StreamNegotiation,
+                )
+            }
+        };
+        let frame = match self
+            .desc
+            .frames()
+            .iter()
+            .find(|f| f.format_index().0 == format.0 && f.resolution() == (width, height))
+        {
+            Some(frame) => frame,
+            None => {
+                return err(
+                    format!(
+                        "format {} of streaming interface {} does not support {}x{}",
+                        format.0,
+                        self.desc.id(),
+                        width,
+                        height
+                    ),
+                    Action::StreamNegotiation,
+                )
+            }
+        };
+        if !(fps.is_finite() && fps > 0.0) {
+            return err(
+                format!("invalid frame rate {}", fps),
+                Action::StreamNegotiation,
+            );
+        }
+        let interval = closest_frame_interval(frame, Duration::from_secs_f64(1.0 / fps));
+
+        let mut stream = self.start_negotiated(format, frame.index(), interval)?;
+        if self.desc.alt_settings().len() > 1 {
+            stream.start_iso()?;
+        } else {
+            stream.check_bulk_bandwidth();
+        }
+        Ok(stream)
+    }
+
+    /// Registers a [`Stream`] on this interface and negotiates its parameters.
+    fn start_negotiated(
+        &mut self,
+        format: FormatIndex,
+        frame: FrameIndex,
+        interval: Duration,
+    ) -> Result<Stream<'a>> {
+        // Register the stream before negotiating, so that a running stream's parameters don't get
+        // replaced.
+        let mut stream = self.start_stream_no_negotiate()?;
+        stream.negotiated = Some(self.negotiate_stream_params(format, frame, interval)?);
+        Ok(stream)
+    }
+
+    /// Starts streaming with the parameters that were last committed to the device.
+    ///
+    /// Like [`StreamingInterface::start_stream`], this fails if a [`Stream`] on this interface is
+    /// still alive.
+    pub fn start_stream_no_negotiate(&mut self) -> Result<Stream<'a>> {
+        self.device.stream_started(self.desc.id())?;
+        Ok(Stream {
             device: self.device,
+            desc: self.desc,
+            encoding: self.encoding.clone(),
+            negotiation_timeout: self.negotiation_timeout,
             ep: self.desc.endpoint_address(),
-        }
+            alt_setting: 0,
+            iso: None,
+            iso_config: IsoStreamConfig::new(),
+            iso_interrupted: false,
+            negotiated: None,
+        })
     }
 
+    /// Negotiates and commits stream parameters, returning the parameters granted by the device.
     fn negotiate_stream_params(
         &mut self,
         format_index: FormatIndex,
         frame_index: FrameIndex,
-    ) -> Result<()> {
-        let frame = self.desc.frame_by_index(frame_index);
-        let interval = frame
-            .as_frame_uncompressed()
-            .unwrap()
-            .default_frame_interval();
-        let interval_100ns = interval.as_secs_f64() / Duration::from_nanos(100).as_secs_f64();
-
-        let controls = ProbeCommitControls {
+        interval: Duration,
+    ) -> Result<NegotiatedParams> {
+        let frame = self.find_frame(format_index, frame_index)?;
+        let mut controls = ProbeCommitControls {
             bFormatIndex: format_index.0,
             bFrameIndex: frame_index.0,
-            dwFrameInterval: interval_100ns as u32,
+            dwFrameInterval: duration_to_100ns(interval),
             ..Default::default()
         };
-        log::debug!("negotiating parameters: {:?}", controls);
-        self.set_control::<Probe>(controls)?;
-        let controls = self.read_control::<Probe>()?;
-        log::debug!("final parameters: {:?}", controls);
-        self.set_control::<Commit>(controls)?;
+        self.apply_encoding_params(format_index, &mut controls)?;
+
+        // Newer UVC versions append fields to the probe and commit controls, and devices expect
+        // the control length matching their version. The additional fields are left at 0, which
+        // lets the device pick its preferred values.
+        let len = probe_commit_len(self.device.uvc_version());
+        let mut buf = [0; PROBE_COMMIT_LEN_MAX];
+        let buf = &mut buf[..len];
+        controls.encode(&mut buf[..mem::size_of::<ProbeCommitControls>()]);
+
+        log::debug!("negotiating parameters ({} bytes): {}", len, controls);
+        self.set_control_raw(ControlId::Probe, buf)?;
+        self.read_control_raw(ControlId::Probe, Request::GetCur, buf)?;
+        let granted = ProbeCommitControls::decode(&buf[..mem::size_of::<ProbeCommitControls>()]);
+        log::debug!("final parameters: {}", granted);
+        check_probe_response(&controls, &granted)?;
+
+        self.set_control_raw(ControlId::Commit, buf)?;
+
+        // `check_probe_response` made sure that the device uses the requested frame.
+        Ok(NegotiatedParams {
+            frame_interval: duration_from_100ns(granted.dwFrameInterval),
+            resolution: frame.resolution(),
+            max_payload_transfer_size: granted.dwMaxPayloadTransferSize,
+            max_video_frame_size: granted.dwMaxVideoFrameSize,
+        })
+    }
+
+    /// Looks up the frame `frame` of the format `format`, returning an error if either doesn't
+    /// exist on this interface.
+    fn find_frame(&self, format: FormatIndex, frame: FrameIndex) -> Result<&'a Frame> {
+        // Indices are 1-based and device-specific, so list the valid ones in the error messages.
+        let list = |indices: &mut dyn Iterator<Item = u8>| {
+            indices
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        if self.desc.try_format_by_index(format).is_none() {
+            return err(
+                format!(
+                    "streaming interface {} has no format with index {} (available: {})",
+                    self.desc.id(),
+                    format.0,
+                    list(&mut self.desc.formats().iter().map(|f| f.index().0)),
+                ),
+                Action::StreamNegotiation,
+            );
+        }
+        match self.desc.try_frame_by_index(format, frame) {
+            Some(f) => Ok(f),
+            None => err(
+                format!(
+                    "format {} of streaming interface {} has no frame with index {} (available: {})",
+                    format.0,
+                    self.desc.id(),
+                    frame.0,
+                    list(
+                        &mut self
+                            .desc
+                            .frames()
+                            .iter()
+                            .filter(|f| f.format_index().0 == format.0)
+                            .map(|f| f.index().0)
+                    ),
+                ),
+                Action::StreamNegotiation,
+            ),
+        }
+    }
+
+    fn apply_encoding_params(
+        &self,
+        format_index: FormatIndex,
+        controls: &mut ProbeCommitControls,
+    ) -> Result<()> {
+        let supported = self
+            .desc
+            .format_controls(format_index)
+            .unwrap_or_else(PerFormatControls::empty);
+        let check = |value: Option<u16>, control, name| match value {
+            Some(_) if !supported.contains(control) => err(
+                format!(
+                    "format {} does not support setting the {}",
+                    format_index.0, name
+                ),
+                Action::StreamNegotiation,
+            ),
+            _ => Ok(value),
+        };
+
+        // The hint bits tell the device to keep the corresponding values fixed.
+        let mut hint = controls.bmHint;
+        if let Some(quality) = check(
+            self.encoding.comp_quality,
+            PerFormatControls::COMP_QUALITY,
+            "compression quality",
+        )? {
+            hint |= ProbeHint::FIX_COMP_QUALITY;
+            controls.wCompQuality = quality;
+        }
+        if let Some(rate) = check(
+            self.encoding.key_frame_rate,
+            PerFormatControls::KEY_FRAME_RATE,
+            "key frame rate",
+        )? {
+            hint |= ProbeHint::FIX_KEY_FRAME_RATE;
+            controls.wKeyFrameRate = rate;
+        }
+        if let Some(rate) = check(
+            self.encoding.p_frame_rate,
+            PerFormatControls::P_FRAME_RATE,
+            "P-frame rate",
+        )? {
+            hint |= ProbeHint::FIX_P_FRAME_RATE;
+            controls.wPFrameRate = rate;
+        }
+        controls.bmHint = hint;
+
         Ok(())
     }
 
+    /// Reads `dwClockFrequency` from the current probe state.
+    ///
+    /// Returns `None` if the device implements UVC 1.0, where the probe control doesn't contain
+    /// the clock frequency.
+    pub(crate) fn probe_clock_frequency(&self) -> Result<Option<u32>> {
+        // `dwClockFrequency` directly follows the fields of `ProbeCommitControls`.
+        const OFFSET: usize = mem::size_of::<ProbeCommitControls>();
+
+        let len = probe_commit_len(self.device.uvc_version());
+        if len < OFFSET + 4 {
+            return Ok(None);
+        }
+
+        let mut buf = [0; PROBE_COMMIT_LEN_MAX];
+        self.read_control_raw(ControlId::Probe, Request::GetCur, &mut buf[..len])?;
+        Ok(Some(u32::decode(&buf[OFFSET..OFFSET + 4])))
+    }
+
+    /// Reads the current probe state in a single request.
+    ///
+    /// On UVC 1.5 devices, this uses `GET_CUR_ALL`, which returns the values of all controls of
+    /// the interface at once, starting with the full probe control. On older devices, it reads
+    /// the probe control with `GET_CUR`, using the control length of the device's UVC version.
+    pub fn read_probe_all(&self) -> Result<ProbeCommitControls> {
+        let mut buf = [0; PROBE_COMMIT_LEN_MAX];
+        if self.device.supports_uvc_1_5() {
+            // `*_ALL` requests address the whole interface rather than a single control.
+            self.read_control_raw(ControlId::Undefined, Request::GetCurAll, &mut buf)?;
+        } else {
+            let len = probe_commit_len(self.device.uvc_version());
+            self.read_control_raw(ControlId::Probe, Request::GetCur, &mut buf[..len])?;
+        }
+        Ok(ProbeCommitControls::decode(
+            &buf[..mem::size_of::<ProbeCommitControls>()],
+        ))
+    }
+
+    /// Reads a single still image payload from the dedicated still image endpoint (capture
+    /// method 3), returning the number of Bytes read.
+    ///
+    /// The still image has to be configured via [`StillProbe`] and [`StillCommit`] and triggered
+    /// beforehand. The interface is claimed when the device is opened, but the still image
+    /// endpoint is only usable while an alternate setting containing it is selected (see
+    /// [`AltSetting::has_still_endpoint`]). That is alternate setting 0 unless a [`Stream`] has
+    /// selected a different one.
+    ///
+    /// Returns an error if the interface doesn't use still image capture method 3, has no still
+    /// image endpoint (see [`StreamingInterfaceDesc::still_endpoint_address`]), or if the endpoint
+    /// is not part of the current alternate setting.
+    pub fn read_still_payload(&self, buf: &mut [u8]) -> Result<usize> {
+        let id = self.desc.id();
+        match self.desc.still_capture_method() {
+            Some(StillCaptureMethod::Method3) => {}
+            Some(method) => {
+                return err(
+                    format!(
+                        "streaming interface {} uses still image capture {:?}, not method 3",
+                        id, method
+                    ),
+                    Action::ReadingStillImage,
+                )
+            }
+            None => {
+                return err(
+                    format!("streaming interface {} is an output interface", id),
+                    Action::ReadingStillImage,
+                )
+            }
+        }
+        let ep = match self.desc.still_endpoint_address() {
+            Some(ep) => ep,
+            None => {
+                return err(
+                    format!("streaming interface {} has no still image endpoint", id),
+                    Action::ReadingStillImage,
+                )
+            }
+        };
+        let alt = self.device.alt_setting(id);
+        let selected = self
+            .desc
+            .alt_settings()
+            .iter()
+            .any(|a| a.number() == alt && a.has_still_endpoint());
+        if !selected {
+            return err(
+                format!(
+                    "still image endpoint {:#04x} is not part of alternate setting {} of streaming interface {}",
+                    ep, alt, id
+                ),
+                Action::ReadingStillImage,
+            );
+        }
+
+        self.device.with_usb(|usb| {
+            usb.read_bulk(ep, buf, self.device.timeout)
+                .during(Action::ReadingStillImage)
+        })
+    }
+
+    /// Reads the stream parameters that were last committed to the device.
+    fn read_committed(&self) -> Result<ProbeCommitControls> {
+        let len = probe_commit_len(self.device.uvc_version());
+        let mut buf = [0; PROBE_COMMIT_LEN_MAX];
+        self.read_control_raw(ControlId::Commit, Request::GetCur, &mut buf[..len])?;
+        Ok(ProbeCommitControls::decode(
+            &buf[..mem::size_of::<ProbeCommitControls>()],
+        ))
+    }
+
     pub fn read_control<C: StreamingControl>(&self) -> Result<C::Value> {
         let mut buf = <<C::Value as ControlValue>::Buf>::default();
         self.read_control_raw(C::ID, Request::GetCur, buf.as_mut())?;
@@ -89,29 +524,860 @@ impl<'a> StreamingInterface<'a> {
     }
 
     fn set_control_raw(&mut self, control: ControlId, value: &[u8]) -> Result<()> {
-        self.device
-            .set_interface_entity(self.desc.id().0, 0, control as _, value)
+        self.device.set_interface_entity(
+            self.desc.id().0,
+            0,
+            control as _,
+            value,
+            self.negotiation_timeout,
+        )
+    }
+
+    fn read_control_raw(&self, control: ControlId, req: Request, buf: &mut [u8]) -> Result<usize> {
+        self.device.read_interface_entity(
+            self.desc.id().0,
+            0,
+            req,
+            control as _,
+            buf,
+            self.negotiation_timeout,
+        )
+    }
+}
+
+/// Sizes the transfers of an isochronous stream.
+///
+/// The host keeps `num_transfers` transfers of `packets_per_transfer` packets (one per service
+/// interval) in flight, so that the device always has a buffer to write into. Together, they take
+/// up `num_transfers * packets_per_transfer` times the endpoint's packet size in memory.
+///
+/// Since a transfer's packets are only handed out once the whole transfer has completed, more
+/// packets per transfer add latency, but reduce the per-transfer overhead. More transfers make the
+/// stream more tolerant of the application reading late, at the cost of memory.
+///
+/// Values that aren't set explicitly are derived from the packet size and `bInterval` of the
+/// selected alternate setting: transfers aim for about 64 KiB, and enough of them are submitted to
+/// cover about 16 ms of streaming.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> ruvc::Result<()> {
+/// use ruvc::streaming_interface::IsoStreamConfig;
+///
+/// let dev = ruvc::list()?.next().unwrap().open()?;
+/// let desc = dev.default_streaming_interface().unwrap();
+/// let frame = &desc.frames()[0];
+/// let mut interface = dev.streaming_interface_by_id(desc.id());
+/// let stream = interface.start_iso_stream_with(
+///     frame.format_index(),
+///     frame.index(),
+///     IsoStreamConfig::new().num_transfers(8),
+/// )?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IsoStreamConfig {
+    num_transfers: Option<usize>,
+    packets_per_transfer: Option<usize>,
+}
+
+/// Default target size of a single isochronous transfer, in Bytes.
+const ISO_TRANSFER_TARGET_SIZE: usize = 64 * 1024;
+
+/// Default amount of streaming time covered by the isochronous transfers in flight.
+const ISO_BUFFERED_TIME: Duration = Duration::from_millis(16);
+
+impl IsoStreamConfig {
+    /// Creates a configuration that derives all values from the selected alternate setting.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of transfers kept in flight (at least 1).
+    pub fn num_transfers(&mut self, num: usize) -> &mut Self {
+        self.num_transfers = Some(num.max(1));
+        self
+    }
+
+    /// Sets the number of packets (service intervals) per transfer (at least 1).
+    pub fn packets_per_transfer(&mut self, num: usize) -> &mut Self {
+        self.packets_per_transfer = Some(num.max(1));
+        self
     }
 
-    fn read_control_raw(&self, control: ControlId, req: Request, buf: &mut [u8]) -> Result<()> {
-        self.device
-            .read_interface_entity(self.desc.id().0, 0, req, control as _, buf)
+    /// Returns `(num_transfers, packets_per_transfer)` for streaming over `alt`.
+    fn resolve(&self, alt: &AltSetting, speed: Speed) -> (usize, usize) {
+        let packet_size = (alt.bytes_per_interval() as usize).max(1);
+        let packets_per_transfer = self
+            .packets_per_transfer
+            .unwrap_or_else(|| (ISO_TRANSFER_TARGET_SIZE / packet_size).clamp(8, 128));
+        let num_transfers = self.num_transfers.unwrap_or_else(|| {
+            let interval = alt
+                .service_interval(speed)
+                .unwrap_or(Duration::from_micros(125));
+            let transfer_time = interval * packets_per_transfer as u32;
+            let num = ISO_BUFFERED_TIME
+                .as_nanos()
+                .div_ceil(transfer_time.as_nanos());
+            (num as usize).clamp(2, 16)
+        });
+        (num_transfers, packets_per_transfer)
+    }
+}
+
+/// Returns the frame interval supported by `frame` that is closest to `target`.
+fn closest_frame_interval(frame: &Frame, target: Duration) -> Duration {
+    match frame.frame_intervals() {
+        SupportedFrameIntervals::Discrete {
+            supported_frame_intervals,
+        } => supported_frame_intervals
+            .iter()
+            .copied()
+            .min_by_key(|interval| interval.abs_diff(target))
+            .unwrap_or_else(|| frame.default_frame_interval()),
+        // The device rounds the interval to a supported step during negotiation.
+        SupportedFrameIntervals::Continuous {
+            min_frame_interval,
+            max_frame_interval,
+            ..
+        } => target.clamp(
+            *min_frame_interval,
+            (*max_frame_interval).max(*min_frame_interval),
+        ),
+    }
+}
+
+/// Picks the alternate setting of `desc` that reserves the least bandwidth while still being able
+/// to transfer `payload_size` Bytes per service interval.
+fn select_alt_setting(desc: &StreamingInterfaceDesc, payload_size: u32) -> Result<AltSetting> {
+    let candidates = desc
+        .alt_settings()
+        .iter()
+        .filter(|alt| alt.endpoint_address().is_some());
+    let selected = candidates
+        .clone()
+        .filter(|alt| alt.bytes_per_interval() >= payload_size)
+        .min_by_key(|alt| alt.bytes_per_interval());
+    match selected {
+        Some(alt) => Ok(*alt),
+        None => err(
+            format!(
+                "no alternate setting of streaming interface {} can transfer {} Byte payloads (largest: {} Bytes)",
+                desc.id(),
+                payload_size,
+                candidates.map(|alt| alt.bytes_per_interval()).max().unwrap_or(0),
+            ),
+            Action::StartingStream,
+        ),
+    }
+}
+
+/// Checks the device's `GET_CUR(PROBE)` response against the values sent with `SET_CUR(PROBE)`.
+///
+/// The device is allowed to adjust some values (eg. pick a supported frame interval), but a
+/// changed format or frame index, or a frame interval of 0, indicates that the device ignored or
+/// misinterpreted the probe (this is what happens on the Leap Motion when sending it a probe
+/// control that is too long). All mismatches are reported in the returned error.
+fn check_probe_response(
+    requested: &ProbeCommitControls,
+    granted: &ProbeCommitControls,
+) -> Result<()> {
+    let mut mismatches = Vec::new();
+
+    let (req_format, got_format) = (requested.bFormatIndex, granted.bFormatIndex);
+    if req_format != got_format {
+        mismatches.push(format!(
+            "format index {} (requested {})",
+            got_format, req_format
+        ));
+    }
+
+    let (req_frame, got_frame) = (requested.bFrameIndex, granted.bFrameIndex);
+    if req_frame != got_frame {
+        mismatches.push(format!(
+            "frame index {} (requested {})",
+            got_frame, req_frame
+        ));
+    }
+
+    let (req_interval, got_interval) = (requested.dwFrameInterval, granted.dwFrameInterval);
+    if got_interval == 0 {
+        mismatches.push(format!(
+            "frame interval 0 (requested {} in 100 ns units)",
+            req_interval
+        ));
+    } else if req_interval != got_interval {
+        log::debug!(
+            "device adjusted frame interval from {} to {} (100 ns units)",
+            req_interval,
+            got_interval
+        );
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        err(
+            format!(
+                "device did not accept the probed parameters, it returned {}",
+                mismatches.join(", ")
+            ),
+            Action::StreamNegotiation,
+        )
     }
 }
 
 pub struct Stream<'a> {
     device: &'a UvcDevice,
+    desc: &'a StreamingInterfaceDesc,
+    encoding: EncodingParams,
+    negotiation_timeout: Duration,
     ep: u8,
+    /// Alternate setting selected for an isochronous stream, 0 for bulk streams.
+    alt_setting: u8,
+    iso: Option<IsoTransfers>,
+    iso_config: IsoStreamConfig,
+    /// Whether the isochronous transfers were stopped by a failed renegotiation, so reads have to
+    /// fail until a renegotiation succeeds.
+    iso_interrupted: bool,
+    negotiated: Option<NegotiatedParams>,
+}
+
+/// Stream parameters granted by the device during negotiation.
+struct NegotiatedParams {
+    frame_interval: Duration,
+    resolution: (u16, u16),
+    max_payload_transfer_size: u32,
+    max_video_frame_size: u32,
+}
+
+impl<'a> Stream<'a> {
+    /// Returns the ID of the streaming interface this stream belongs to.
+    pub fn interface_id(&self) -> StreamingInterfaceId {
+        self.desc.id()
+    }
+
+    /// Returns the address of the USB endpoint this stream reads from.
+    pub fn endpoint_address(&self) -> u8 {
+        self.ep
+    }
+
+    /// Returns the frame interval committed during stream negotiation.
+    ///
+    /// This can differ from the interval that was requested, since the device may adjust it during
+    /// negotiation. Returns `None` if the stream was started without negotiation.
+    pub fn frame_interval(&self) -> Option<Duration> {
+        self.negotiated.as_ref().map(|p| p.frame_interval)
+    }
+
+    /// Returns the `(width, height)` of the frame committed during stream negotiation.
+    ///
+    /// Returns `None` if the stream was started without negotiation.
+    pub fn resolution(&self) -> Option<(u16, u16)> {
+        self.negotiated.as_ref().map(|p| p.resolution)
+    }
+
+    /// Negotiates new stream parameters without stopping the stream.
+    ///
+    /// This is only supported by input interfaces that report
+    /// [`InputInterfaceInfo::DYNAMIC_FORMAT_CHANGE_SUPPORTED`]; an error is returned for all other
+    /// interfaces. Encoder parameters set on the [`StreamingInterface`] before the stream was
+    /// started are requested again.
+    ///
+    /// Isochronous streams are stopped by switching to alternate setting 0 during negotiation, and
+    /// then restarted with the alternate setting that fits the new `dwMaxPayloadTransferSize`. If
+    /// negotiating or restarting fails, the stream stays stopped: reads return an error until a
+    /// later call to this method succeeds.
+    pub fn renegotiate(
+        &mut self,
+        format: FormatIndex,
+        frame: FrameIndex,
+        interval: Duration,
+    ) -> Result<()> {
+        let supported = self
+            .desc
+            .input_info()
+            .is_some_and(|info| info.contains(InputInterfaceInfo::DYNAMIC_FORMAT_CHANGE_SUPPORTED));
+        if !supported {
+            return err(
+                format!(
+                    "streaming interface {} does not support dynamic format changes",
+                    self.desc.id().0
+                ),
+                Action::StreamNegotiation,
+            );
+        }
+
+        let iso = self.iso.is_some() || self.iso_interrupted;
+        if iso {
+            // Cleared once the stream is restarted. If anything below fails, reads report an error
+            // instead of falling back to bulk transfers on the isochronous endpoint.
+            self.iso_interrupted = true;
+            self.stop_iso().during(Action::StreamNegotiation)?;
+        }
+        self.negotiated = Some(
+            self.interface()
+                .negotiate_stream_params(format, frame, interval)?,
+        );
+        if iso {
+            self.start_iso()?;
+            self.iso_interrupted = false;
+        } else {
+            self.check_bulk_bandwidth();
+        }
+        Ok(())
+    }
+
+    /// Logs a warning if the negotiated parameters need more bandwidth than
+    /// `available_bits_per_sec`, the theoretical maximum of `limit`.
+    ///
+    /// Such a stream typically starts, but then stalls or drops most of its frames.
+    fn check_bandwidth(&self, available_bits_per_sec: u64, limit: fmt::Arguments<'_>) {
+        let params = match &self.negotiated {
+            Some(params) => params,
+            None => return,
+        };
+        let interval_ns = params.frame_interval.as_nanos();
+        if interval_ns == 0 {
+            return;
+        }
+
+        let frame_size = params.max_video_frame_size;
+        let required_bits_per_sec =
+            (u128::from(frame_size) * 8 * 1_000_000_000 / interval_ns) as u64;
+        if required_bits_per_sec > available_bits_per_sec {
+            log::warn!(
+                "stream needs {} Mbit/s ({} Byte frames every {:?}), but {} only provides {} Mbit/s",
+                required_bits_per_sec / 1_000_000,
+                frame_size,
+                params.frame_interval,
+                limit,
+                available_bits_per_sec / 1_000_000,
+            );
+        }
+    }
+
+    /// Checks the bandwidth of a bulk stream against the raw bit rate of the USB link.
+    ///
+    /// Bulk endpoints don't reserve bandwidth, so the link speed is the only known limit.
+    fn check_bulk_bandwidth(&self) {
+        let speed = self.device.usb_speed();
+        let link_bits_per_sec: u64 = match speed {
+            Speed::Low => 1_500_000,
+            Speed::Full => 12_000_000,
+            Speed::High => 480_000_000,
+            Speed::Super => 5_000_000_000,
+            Speed::SuperPlus => 10_000_000_000,
+            _ => return,
+        };
+        self.check_bandwidth(
+            link_bits_per_sec,
+            format_args!("the USB link at {:?} speed", speed),
+        );
+    }
+
+    /// Selects the alternate setting that fits the negotiated `dwMaxPayloadTransferSize`, and
+    /// starts the isochronous transfers.
+    fn start_iso(&mut self) -> Result<()> {
+        let payload_size = self
+            .negotiated
+            .as_ref()
+            .map_or(0, |p| p.max_payload_transfer_size);
+        let alt = select_alt_setting(self.desc, payload_size)?;
+        // unwrap: `select_alt_setting` only returns alternate settings with an endpoint
+        let ep = alt.endpoint_address().unwrap();
+        let speed = self.device.usb_speed();
+        let (num_transfers, packets_per_transfer) = self.iso_config.resolve(&alt, speed);
+        log::debug!(
+            "using alternate setting {} ({} Bytes per interval) for {} Byte payloads, with {} transfers of {} packets",
+            alt.number(),
+            alt.bytes_per_interval(),
+            payload_size,
+            num_transfers,
+            packets_per_transfer,
+        );
+
+        // The alternate setting reserves `bytes_per_interval` Bytes every service interval.
+        if let Some(interval) = alt.service_interval(speed) {
+            let bits_per_sec =
+                u128::from(alt.bytes_per_interval()) * 8 * 1_000_000_000 / interval.as_nanos();
+            self.check_bandwidth(
+                bits_per_sec as u64,
+                format_args!("alternate setting {}", alt.number()),
+            );
+        }
+
+        self.device.with_usb(|_| {
+            self.device
+                .set_alt_setting(self.desc.id(), alt.number())
+                .during(Action::StartingStream)
+        })?;
+        self.alt_setting = alt.number();
+        self.ep = ep;
+        self.iso = Some(
+            IsoTransfers::start(
+                self.device.usb_handle(),
+                ep,
+                alt.bytes_per_interval() as usize,
+                num_transfers,
+                packets_per_transfer,
+                self.device.timeout,
+            )
+            .during(Action::StartingStream)?,
+        );
+        Ok(())
+    }
+
+    /// Cancels the isochronous transfers and switches back to alternate setting 0.
+    fn stop_iso(&mut self) -> rusb::Result<()> {
+        // The transfers have to be cancelled before the endpoint goes away.
+        self.iso = None;
+        if self.alt_setting != 0 {
+            // Alternate setting 0 releases the bandwidth reserved by the isochronous endpoint.
+            self.device.set_alt_setting(self.desc.id(), 0)?;
+            self.alt_setting = 0;
+        }
+        Ok(())
+    }
+
+    /// Reads a single payload and splits it into its header and data.
+    ///
+    /// `buf` has to be large enough to hold a whole payload, which is at most the
+    /// `dwMaxPayloadTransferSize` negotiated for the stream. Returns an error if the payload
+    /// header is malformed, including when its `bHeaderLength` exceeds the received data.
+    pub fn read_payload<'b>(&mut self, buf: &'b mut [u8]) -> Result<(PayloadHeader, &'b [u8])> {
+        let len = self.read_raw(buf)?;
+        PayloadHeader::parse(&buf[..len])
+    }
+
+    fn read_raw(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let ep = self.ep;
+        if self.iso_interrupted {
+            return err(
+                "the stream was stopped because renegotiating its parameters failed",
+                Action::StreamRead { endpoint: ep },
+            );
+        }
+        match &mut self.iso {
+            Some(iso) => iso
+                .read_packet(buf)
+                .during(Action::StreamRead { endpoint: ep }),
+            None => self.device.with_usb(|usb| {
+                usb.read_bulk(ep, buf, self.device.timeout)
+                    .during(Action::StreamRead { endpoint: ep })
+            }),
+        }
+    }
+
+    /// Returns a [`StreamingInterface`] with the settings this stream was started with.
+    fn interface(&self) -> StreamingInterface<'a> {
+        StreamingInterface {
+            device: self.device,
+            desc: self.desc,
+            encoding: self.encoding.clone(),
+            negotiation_timeout: self.negotiation_timeout,
+        }
+    }
 }
 
+impl Drop for Stream<'_> {
+    fn drop(&mut self) {
+        if let Err(e) = self.stop_iso() {
+            log::warn!(
+                "failed to reset streaming interface {} to alternate setting 0: {}",
+                self.desc.id(),
+                e
+            );
+        }
+        self.device.stream_stopped(self.desc.id());
+    }
+}
+
+/// Reads raw data from the stream's endpoint.
+///
+/// The data includes the UVC payload headers. Use [`Stream::read_payload`] to have them parsed
+/// and stripped.
+///
+/// For bulk streams, each call performs a single USB transfer. It is normal for a read to return
+/// `Ok(n)` with `n` smaller than the buffer size: the device may end a transfer early, and if the
+/// transfer times out after some data was already received, that partial data is returned instead
+/// of an error.
+///
+/// For isochronous streams, each call returns the data of a single isochronous packet. Empty
+/// packets are skipped.
+///
+/// If a read times out without receiving any data, an error of kind
+/// [`io::ErrorKind::TimedOut`] is returned.
 impl Read for Stream<'_> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.device
-            .with_usb(|usb| {
-                usb.read_bulk(self.ep, buf, self.device.timeout)
-                    .during(Action::StreamRead)
-            })
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        self.read_raw(buf).map_err(|e| {
+            let kind = if e.is_usb_timeout() {
+                io::ErrorKind::TimedOut
+            } else {
+                io::ErrorKind::Other
+            };
+            io::Error::new(kind, e)
+        })
+    }
+}
+
+/// Reassembles the payloads of a [`Stream`] into complete video frames.
+///
+/// A frame ends when the device sets the end-of-frame bit in a payload header, or when the frame
+/// ID bit toggles (not all devices set the end-of-frame bit). Payloads of still images are
+/// treated like those of video frames.
+pub struct FrameReader<'a> {
+    stream: Stream<'a>,
+    assembler: FrameAssembler,
+}
+
+impl<'a> FrameReader<'a> {
+    /// Creates a `FrameReader` reading from `stream`.
+    ///
+    /// The buffer sizes are taken from the `dwMaxPayloadTransferSize` and `dwMaxVideoFrameSize`
+    /// granted during negotiation. If the stream was started without negotiation, they are read
+    /// from the device's committed parameters instead.
+    pub fn new(stream: Stream<'a>) -> Result<Self> {
+        let (payload_size, frame_size) = match &stream.negotiated {
+            Some(params) => (
+                params.max_payload_transfer_size,
+                params.max_video_frame_size,
+            ),
+            None => {
+                let committed = stream.interface().read_committed()?;
+                (
+                    committed.dwMaxPayloadTransferSize,
+                    committed.dwMaxVideoFrameSize,
+                )
+            }
+        };
+
+        let assembler = FrameAssembler::new(payload_size as usize, frame_size as usize, stream.ep);
+        Ok(Self { stream, assembler })
+    }
+
+    /// Returns the underlying stream.
+    pub fn stream(&self) -> &Stream<'a> {
+        &self.stream
+    }
+
+    /// Consumes the `FrameReader`, returning the underlying stream.
+    ///
+    /// The data of a partially received frame is lost.
+    pub fn into_inner(self) -> Stream<'a> {
+        self.stream
+    }
+
+    /// Reads payloads until a frame is complete, and returns the frame's data.
+    ///
+    /// If the device flags a payload as erroneous, or a frame exceeds `dwMaxVideoFrameSize`, an
+    /// error is returned and the rest of the affected frame is dropped. Reading can continue with
+    /// the next call.
+    pub fn next_frame(&mut self) -> Result<&[u8]> {
+        let stream = &mut self.stream;
+        self.assembler.next_frame(|buf| stream.read_raw(buf))
+    }
+
+    /// Waits for the first complete, error-free frame of a freshly started stream, and returns its
+    /// data.
+    ///
+    /// Devices often send erroneous or empty payloads right after the stream was started, and the
+    /// first payloads might belong to a frame whose start was never received. This drops all data
+    /// until the first frame boundary (a toggled frame ID or the end-of-frame bit), and then skips
+    /// frames that can't be reassembled (like [`FrameReader::next_frame`] reports them) until a
+    /// frame is received.
+    ///
+    /// If no frame arrives within `timeout`, an error is returned for which
+    /// [`io::ErrorKind::TimedOut`] is reported when converting it to an [`io::Error`]. Since a
+    /// single USB transfer isn't interrupted, the wait can exceed `timeout` by up to the device's
+    /// transfer timeout. Other USB errors are returned immediately.
+    pub fn read_first_frame(&mut self, timeout: Duration) -> Result<&[u8]> {
+        let deadline = Instant::now() + timeout;
+        let stream = &mut self.stream;
+        self.assembler
+            .first_frame(deadline, |buf| stream.read_raw(buf))
+    }
+
+    /// Reads payloads until a frame is complete, and copies the frame's data into `buf`.
+    ///
+    /// `buf` is cleared first, so the same buffer can be reused for every frame without allocating
+    /// once it has grown to the frame size. Returns the frame's metadata.
+    ///
+    /// Errors are handled like in [`FrameReader::next_frame`]: frames the device flags as
+    /// erroneous are dropped and reported as an error, so they never reach `buf`.
+    pub fn read_frame_into(&mut self, buf: &mut Vec<u8>) -> Result<FrameMeta> {
+        let stream = &mut self.stream;
+        self.assembler
+            .read_frame_into(buf, |buf| stream.read_raw(buf))
+    }
+}
+
+/// Metadata of a reassembled frame, returned by [`FrameReader::read_frame_into`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameMeta {
+    len: usize,
+    pts: Option<u32>,
+    scr: Option<(u32, u16)>,
+    still_image: bool,
+}
+
+impl FrameMeta {
+    /// Returns the length of the frame's data in Bytes.
+    pub fn data_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the presentation time stamp of the frame, from the first payload header that
+    /// contained one.
+    ///
+    /// See [`PayloadHeader::presentation_time`].
+    pub fn presentation_time(&self) -> Option<u32> {
+        self.pts
+    }
+
+    /// Returns the source clock reference of the last payload header of the frame that contained
+    /// one.
+    ///
+    /// See [`PayloadHeader::source_clock`].
+    pub fn source_clock(&self) -> Option<(u32, u16)> {
+        self.scr
+    }
+
+    /// Returns whether the frame is a still image.
+    pub fn is_still_image(&self) -> bool {
+        self.still_image
+    }
+}
+
+/// Reads complete MJPEG frames from a [`FrameReader`], dropping malformed ones.
+///
+/// Each frame has to start with the JPEG start-of-image marker (`FF D8`) and end with the
+/// end-of-image marker (`FF D9`). Frames that don't were most likely truncated by lost transfers,
+/// and would make JPEG decoders fail, so they are dropped with a warning.
+///
+/// As an [`Iterator`], the reader yields each valid frame as a `Vec<u8>` and never ends. Errors of
+/// the underlying [`FrameReader`] are yielded as well, reading can continue after them.
+pub struct MjpegReader<'a> {
+    frames: FrameReader<'a>,
+}
+
+impl<'a> MjpegReader<'a> {
+    /// Creates an `MjpegReader` reading from `frames`, which has to belong to a stream using an
+    /// MJPEG format.
+    pub fn new(frames: FrameReader<'a>) -> Self {
+        Self { frames }
+    }
+
+    /// Consumes the `MjpegReader`, returning the underlying [`FrameReader`].
+    pub fn into_inner(self) -> FrameReader<'a> {
+        self.frames
+    }
+
+    /// Reads frames until a complete JPEG image was received, and returns it.
+    pub fn next_frame(&mut self) -> Result<&[u8]> {
+        loop {
+            let frame = self.frames.next_frame()?;
+            if is_complete_jpeg(frame) {
+                break;
+            }
+            log::warn!(
+                "dropping malformed MJPEG frame of {} Bytes (missing SOI or EOI marker)",
+                frame.len()
+            );
+        }
+        Ok(&self.frames.assembler.complete)
+    }
+}
+
+impl Iterator for MjpegReader<'_> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_frame().map(<[u8]>::to_vec))
+    }
+}
+
+/// Returns whether `frame` starts with a JPEG SOI marker and ends with an EOI marker.
+fn is_complete_jpeg(frame: &[u8]) -> bool {
+    const SOI: [u8; 2] = [0xff, 0xd8];
+    const EOI: [u8; 2] = [0xff, 0xd9];
+
+    frame.len() >= 4 && frame.starts_with(&SOI) && frame.ends_with(&EOI)
+}
+
+/// The frame reassembly logic of [`FrameReader`], independent of where the payloads come from.
+struct FrameAssembler {
+    payload: Vec<u8>,
+    /// The frame that is being assembled.
+    frame: Vec<u8>,
+    /// The last completed frame, returned by `next_frame`.
+    complete: Vec<u8>,
+    /// Metadata collected from the payload headers of `frame`.
+    meta: FrameMeta,
+    /// Metadata of `complete`.
+    complete_meta: FrameMeta,
+    max_frame_size: usize,
+    /// Endpoint the payloads are read from, for error messages.
+    endpoint: u8,
+    /// Frame ID bit of the last payload, `None` before the first payload was received.
+    frame_id: Option<bool>,
+    /// Whether the rest of the current frame is dropped because of an earlier error.
+    discarding: bool,
+    /// Whether a frame boundary was received, after which frames are known to be complete.
+    synced: bool,
+    /// A payload that was received, but not yet added to a frame (its header and the range of
+    /// its data in `payload`).
+    deferred: Option<(PayloadHeader, Range<usize>)>,
+}
+
+impl FrameAssembler {
+    fn new(max_payload_size: usize, max_frame_size: usize, endpoint: u8) -> Self {
+        Self {
+            payload: vec![0; max_payload_size],
+            frame: Vec::with_capacity(max_frame_size),
+            complete: Vec::with_capacity(max_frame_size),
+            meta: FrameMeta::default(),
+            complete_meta: FrameMeta::default(),
+            max_frame_size,
+            endpoint,
+            frame_id: None,
+            discarding: false,
+            synced: false,
+            deferred: None,
+        }
+    }
+
+    /// Reads payloads with `read_payload` until a frame is complete.
+    ///
+    /// `read_payload` reads a single raw payload (including its header) into the given buffer and
+    /// returns its length.
+    fn next_frame(
+        &mut self,
+        mut read_payload: impl FnMut(&mut [u8]) -> Result<usize>,
+    ) -> Result<&[u8]> {
+        loop {
+            let (header, data) = match self.deferred.take() {
+                Some(deferred) => deferred,
+                None => {
+                    let len = read_payload(&mut self.payload)?;
+                    let (header, data) = PayloadHeader::parse(&self.payload[..len])?;
+                    let start = usize::from(header.header_len());
+                    (header, start..start + data.len())
+                }
+            };
+
+            // A toggled frame ID means that the previous frame ended without an end-of-frame bit.
+            if self.frame_id.is_some_and(|id| id != header.frame_id()) {
+                self.frame_id = None;
+                if self.finish_frame() {
+                    // This payload belongs to the next frame, process it in the next call.
+                    self.deferred = Some((header, data));
+                    return Ok(&self.complete);
+                }
+            }
+            self.frame_id = Some(header.frame_id());
+
+            let endpoint = self.endpoint;
+            if header.is_error() {
+                self.frame.clear();
+                self.discarding = true;
+                return err(
+                    "device flagged a payload as erroneous, dropping the frame",
+                    Action::StreamRead { endpoint },
+                );
+            }
+            if !self.discarding {
+                if self.frame.len() + data.len() > self.max_frame_size {
+                    self.frame.clear();
+                    self.discarding = true;
+                    return err(
+                        format!(
+                            "frame exceeds the negotiated maximum frame size of {} Bytes, dropping it",
+                            self.max_frame_size
+                        ),
+                        Action::StreamRead { endpoint },
+                    );
+                }
+                self.frame.extend_from_slice(&self.payload[data]);
+                self.meta.pts = self.meta.pts.or_else(|| header.presentation_time());
+                self.meta.scr = header.source_clock().or(self.meta.scr);
+                self.meta.still_image |= header.is_still_image();
+            }
+
+            if header.is_end_of_frame() {
+                // The next payload starts a new frame, even if its frame ID doesn't toggle.
+                self.frame_id = None;
+                if self.finish_frame() {
+                    return Ok(&self.complete);
+                }
+            }
+        }
+    }
+
+    /// Reads payloads with `read_payload` until a complete frame following a frame boundary was
+    /// received, or `deadline` passes.
+    fn first_frame(
+        &mut self,
+        deadline: Instant,
+        mut read_payload: impl FnMut(&mut [u8]) -> Result<usize>,
+    ) -> Result<&[u8]> {
+        if !self.synced {
+            // The data received so far might be the end of a frame whose start was missed.
+            self.frame.clear();
+            self.discarding = true;
+        }
+
+        loop {
+            if Instant::now() >= deadline {
+                return err(
+                    rusb::Error::Timeout,
+                    Action::StreamRead {
+                        endpoint: self.endpoint,
+                    },
+                );
+            }
+            match self.next_frame(&mut read_payload) {
+                Ok(_) => break,
+                Err(e) if e.is_usb_timeout() || !e.is_usb_error() => {
+                    log::debug!("waiting for the first frame: {}", e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(&self.complete)
+    }
+
+    /// Like `next_frame`, but copies the frame into `buf` and returns its metadata.
+    fn read_frame_into(
+        &mut self,
+        buf: &mut Vec<u8>,
+        read_payload: impl FnMut(&mut [u8]) -> Result<usize>,
+    ) -> Result<FrameMeta> {
+        let frame = self.next_frame(read_payload)?;
+        buf.clear();
+        buf.extend_from_slice(frame);
+        Ok(self.complete_meta)
+    }
+
+    /// Ends the current frame.
+    ///
+    /// Returns whether the frame was received without errors, in which case it is moved to
+    /// `self.complete`.
+    fn finish_frame(&mut self) -> bool {
+        let complete = !self.discarding && !self.frame.is_empty();
+        if complete {
+            mem::swap(&mut self.frame, &mut self.complete);
+            self.complete_meta = FrameMeta {
+                len: self.complete.len(),
+                ..self.meta
+            };
+        }
+        self.frame.clear();
+        self.meta = FrameMeta::default();
+        self.discarding = false;
+        self.synced = true;
+        complete
     }
 }
 
@@ -148,3 +1414,239 @@ impl StreamingControl for Commit {
     type Value = ProbeCommitControls;
     const ID: ControlId = ControlId::Commit;
 }
+
+/// Still image parameters for Method 2 and 3 still image capture.
+///
+/// Read this control with `GET_CUR` after setting it to learn the `dwMaxVideoFrameSize` and
+/// `dwMaxPayloadTransferSize` of the still image, which are needed to size the capture buffer.
+pub struct StillProbe;
+impl StreamingControl for StillProbe {
+    type Value = StillProbeCommitControls;
+    const ID: ControlId = ControlId::StillProbe;
+}
+
+pub struct StillCommit;
+impl StreamingControl for StillCommit {
+    type Value = StillProbeCommitControls;
+    const ID: ControlId = ControlId::StillCommit;
+}
+
+/// The device's synchronization delay in milliseconds.
+///
+/// This is the delay between the video and audio streams of the device.
+pub struct SynchDelay;
+impl StreamingControl for SynchDelay {
+    type Value = u16;
+    const ID: ControlId = ControlId::SynchDelay;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::payload::PayloadHeaderFlags;
+
+    /// Builds a raw payload with a 2-Byte header.
+    fn payload(flags: PayloadHeaderFlags, data: &[u8]) -> Vec<u8> {
+        let mut payload = vec![2, (flags | PayloadHeaderFlags::END_OF_HEADER).bits()];
+        payload.extend_from_slice(data);
+        payload
+    }
+
+    fn fid(frame_id: bool) -> PayloadHeaderFlags {
+        if frame_id {
+            PayloadHeaderFlags::FRAME_ID
+        } else {
+            PayloadHeaderFlags::empty()
+        }
+    }
+
+    struct Source(VecDeque<Vec<u8>>);
+
+    impl Source {
+        fn new(payloads: Vec<Vec<u8>>) -> Self {
+            Self(payloads.into())
+        }
+
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            match self.0.pop_front() {
+                Some(payload) => {
+                    buf[..payload.len()].copy_from_slice(&payload);
+                    Ok(payload.len())
+                }
+                None => err("no more payloads", Action::StreamRead { endpoint: 0x81 }),
+            }
+        }
+    }
+
+    fn next_frame(assembler: &mut FrameAssembler, source: &mut Source) -> Result<Vec<u8>> {
+        assembler
+            .next_frame(|buf| source.read(buf))
+            .map(<[u8]>::to_vec)
+    }
+
+    #[test]
+    fn frame_id_toggle() {
+        let mut assembler = FrameAssembler::new(64, 64, 0x81);
+        let mut source = Source::new(vec![
+            payload(fid(false), b"ab"),
+            payload(fid(false), b"cd"),
+            payload(fid(true), b"ef"),
+            payload(fid(true), b"gh"),
+            payload(fid(false), b"ij"),
+        ]);
+
+        assert_eq!(next_frame(&mut assembler, &mut source).unwrap(), b"abcd");
+        assert_eq!(next_frame(&mut assembler, &mut source).unwrap(), b"efgh");
+        // The third frame never ends, so reading fails once the source runs dry.
+        assert!(next_frame(&mut assembler, &mut source).is_err());
+    }
+
+    #[test]
+    fn end_of_frame() {
+        let eof = PayloadHeaderFlags::END_OF_FRAME;
+        let mut assembler = FrameAssembler::new(64, 64, 0x81);
+        let mut source = Source::new(vec![
+            payload(fid(false), b"ab"),
+            payload(fid(false) | eof, b"cd"),
+            // Some devices don't toggle the frame ID after an end-of-frame bit.
+            payload(fid(false), b"ef"),
+            payload(fid(false) | eof, b"gh"),
+            payload(fid(true) | eof, b"ij"),
+        ]);
+
+        assert_eq!(next_frame(&mut assembler, &mut source).unwrap(), b"abcd");
+        assert_eq!(next_frame(&mut assembler, &mut source).unwrap(), b"efgh");
+        assert_eq!(next_frame(&mut assembler, &mut source).unwrap(), b"ij");
+    }
+
+    #[test]
+    fn error_recovery() {
+        let error = PayloadHeaderFlags::ERROR;
+        let mut assembler = FrameAssembler::new(64, 64, 0x81);
+        let mut source = Source::new(vec![
+            payload(fid(false), b"ab"),
+            payload(fid(false) | error, b""),
+            payload(fid(false), b"cd"),
+            payload(fid(true), b"ef"),
+            payload(fid(false), b"gh"),
+        ]);
+
+        assert!(next_frame(&mut assembler, &mut source).is_err());
+        // The rest of the broken frame is dropped, and reading resumes with the next one.
+        assert_eq!(next_frame(&mut assembler, &mut source).unwrap(), b"ef");
+    }
+
+    #[test]
+    fn max_frame_size() {
+        let mut assembler = FrameAssembler::new(64, 4, 0x81);
+        let mut source = Source::new(vec![
+            payload(fid(false), b"abc"),
+            payload(fid(false), b"def"),
+            payload(fid(false), b"ghi"),
+            payload(fid(true), b"jk"),
+            payload(fid(true), b"lm"),
+            payload(fid(false), b""),
+        ]);
+
+        assert!(next_frame(&mut assembler, &mut source).is_err());
+        assert_eq!(next_frame(&mut assembler, &mut source).unwrap(), b"jklm");
+    }
+
+    #[test]
+    fn jpeg_validation() {
+        let eof = PayloadHeaderFlags::END_OF_FRAME;
+        let mut assembler = FrameAssembler::new(64, 64, 0x81);
+        let mut source = Source::new(vec![
+            payload(fid(false), &[0xff, 0xd8, 0x01]),
+            payload(fid(false) | eof, &[0x02, 0xff, 0xd9]),
+            // The end of this frame was lost.
+            payload(fid(true), &[0xff, 0xd8, 0x01]),
+            payload(fid(false) | eof, &[0x02, 0xff, 0xd9]),
+        ]);
+
+        let frame = next_frame(&mut assembler, &mut source).unwrap();
+        assert!(is_complete_jpeg(&frame));
+        let frame = next_frame(&mut assembler, &mut source).unwrap();
+        assert!(!is_complete_jpeg(&frame));
+        // The start of this frame belonged to the previous one.
+        let frame = next_frame(&mut assembler, &mut source).unwrap();
+        assert!(!is_complete_jpeg(&frame));
+
+        assert!(!is_complete_jpeg(&[0xff, 0xd8, 0xd9]));
+        assert!(!is_complete_jpeg(&[]));
+    }
+
+    #[test]
+    fn frame_meta() {
+        let pts = PayloadHeaderFlags::PRESENTATION_TIME;
+        let scr = PayloadHeaderFlags::SOURCE_CLOCK;
+        let eof = PayloadHeaderFlags::END_OF_FRAME;
+        let with_fields = |flags: PayloadHeaderFlags, fields: &[u8], data: &[u8]| {
+            let flags = flags | PayloadHeaderFlags::END_OF_HEADER;
+            let mut payload = vec![2 + fields.len() as u8, flags.bits()];
+            payload.extend_from_slice(fields);
+            payload.extend_from_slice(data);
+            payload
+        };
+        let mut assembler = FrameAssembler::new(64, 64, 0x81);
+        let mut source = Source::new(vec![
+            with_fields(pts, &[1, 0, 0, 0], b"ab"),
+            with_fields(pts | scr, &[2, 0, 0, 0, 3, 0, 0, 0, 4, 0], b"cd"),
+            with_fields(eof | PayloadHeaderFlags::STILL_IMAGE, &[], b"ef"),
+            payload(fid(true) | eof, b"gh"),
+        ]);
+
+        let mut buf = b"old data".to_vec();
+        let meta = assembler
+            .read_frame_into(&mut buf, |buf| source.read(buf))
+            .unwrap();
+        assert_eq!(buf, b"abcdef");
+        assert_eq!(meta.data_len(), 6);
+        assert_eq!(meta.presentation_time(), Some(1));
+        assert_eq!(meta.source_clock(), Some((3, 4)));
+        assert!(meta.is_still_image());
+
+        let meta = assembler
+            .read_frame_into(&mut buf, |buf| source.read(buf))
+            .unwrap();
+        assert_eq!(buf, b"gh");
+        assert_eq!(meta.data_len(), 2);
+        assert_eq!(meta.presentation_time(), None);
+        assert_eq!(meta.source_clock(), None);
+        assert!(!meta.is_still_image());
+    }
+
+    #[test]
+    fn first_frame() {
+        let error = PayloadHeaderFlags::ERROR;
+        let mut assembler = FrameAssembler::new(64, 64, 0x81);
+        let mut source = Source::new(vec![
+            // The end of a frame whose start was missed.
+            payload(fid(false), b"ab"),
+            payload(fid(true), b"cd"),
+            payload(fid(true) | error, b""),
+            payload(fid(false), b"ef"),
+            payload(fid(true), b"gh"),
+        ]);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let frame = assembler
+            .first_frame(deadline, |buf| source.read(buf))
+            .unwrap();
+        assert_eq!(frame, b"ef");
+    }
+
+    #[test]
+    fn first_frame_timeout() {
+        let mut assembler = FrameAssembler::new(64, 64, 0x81);
+        let mut source = Source::new(vec![payload(fid(false), b"ab")]);
+
+        let deadline = Instant::now() + Duration::from_millis(10);
+        let err = assembler
+            .first_frame(deadline, |buf| source.read(buf))
+            .unwrap_err();
+        assert!(err.is_usb_timeout());
+    }
+}
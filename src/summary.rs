@@ -0,0 +1,125 @@
+use rusb::Speed;
+
+use crate::{
+    camera,
+    error::{Action, ResultExt},
+    processing_unit,
+    topo::{CameraId, ProcessingUnitId},
+    BcdVersion, Result, UvcDevice,
+};
+
+/// An overview of a UVC device's identity and capabilities.
+///
+/// Returned by [`UvcDevice::summary`]. The [`Debug`] output of this type is suitable for
+/// inclusion in bug reports.
+#[derive(Debug, Clone)]
+pub struct DeviceSummary {
+    vendor_id: u16,
+    product_id: u16,
+    manufacturer: Option<String>,
+    product: Option<String>,
+    usb_speed: Speed,
+    uvc_version: BcdVersion,
+    num_streaming_interfaces: usize,
+    resolutions: Vec<(u16, u16)>,
+    camera_controls: Vec<(CameraId, Vec<camera::ControlId>)>,
+    processing_unit_controls: Vec<(ProcessingUnitId, Vec<processing_unit::ControlId>)>,
+}
+
+impl DeviceSummary {
+    pub(crate) fn new(device: &UvcDevice) -> Result<Self> {
+        let usb = device.usb_handle();
+        let desc = usb
+            .device()
+            .device_descriptor()
+            .during(Action::AccessingDeviceDescriptor)?;
+
+        // String descriptors are optional, only read the ones the device declares.
+        let manufacturer = match desc.manufacturer_string_index() {
+            Some(_) => Some(device.read_manufacturer_string()?),
+            None => None,
+        };
+        let product = match desc.product_string_index() {
+            Some(_) => Some(device.read_product_string()?),
+            None => None,
+        };
+
+        let topo = device.topology();
+        let camera_controls = topo
+            .inputs()
+            .iter()
+            .filter_map(|input| input.as_camera_id())
+            .map(|id| (id, device.camera_terminal_by_id(id).supported_controls()))
+            .collect();
+        let processing_unit_controls = topo
+            .units()
+            .iter()
+            .filter_map(|unit| unit.as_processing_unit())
+            .map(|pu| {
+                let id = pu.id();
+                (id, device.processing_unit_by_id(id).supported_controls())
+            })
+            .collect();
+
+        Ok(Self {
+            vendor_id: desc.vendor_id(),
+            product_id: desc.product_id(),
+            manufacturer,
+            product,
+            usb_speed: device.usb_speed(),
+            uvc_version: device.uvc_version(),
+            num_streaming_interfaces: device.streaming_interfaces().len(),
+            resolutions: device.supported_resolutions(),
+            camera_controls,
+            processing_unit_controls,
+        })
+    }
+
+    pub fn vendor_id(&self) -> u16 {
+        self.vendor_id
+    }
+
+    pub fn product_id(&self) -> u16 {
+        self.product_id
+    }
+
+    /// Returns the manufacturer string, or `None` if the device doesn't have one.
+    pub fn manufacturer(&self) -> Option<&str> {
+        self.manufacturer.as_deref()
+    }
+
+    /// Returns the product string, or `None` if the device doesn't have one.
+    pub fn product(&self) -> Option<&str> {
+        self.product.as_deref()
+    }
+
+    /// Returns the speed the device is operating at.
+    pub fn usb_speed(&self) -> Speed {
+        self.usb_speed
+    }
+
+    pub fn uvc_version(&self) -> BcdVersion {
+        self.uvc_version
+    }
+
+    pub fn num_streaming_interfaces(&self) -> usize {
+        self.num_streaming_interfaces
+    }
+
+    /// Returns the sorted, de-duplicated resolutions supported by any streaming interface.
+    pub fn resolutions(&self) -> &[(u16, u16)] {
+        &self.resolutions
+    }
+
+    /// Returns the supported controls of every camera terminal.
+    pub fn camera_controls(&self) -> &[(CameraId, Vec<camera::ControlId>)] {
+        &self.camera_controls
+    }
+
+    /// Returns the supported controls of every processing unit.
+    pub fn processing_unit_controls(
+        &self,
+    ) -> &[(ProcessingUnitId, Vec<processing_unit::ControlId>)] {
+        &self.processing_unit_controls
+    }
+}
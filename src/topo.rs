@@ -9,15 +9,40 @@
 
 pub(crate) mod parse;
 
-use std::{num::NonZeroU8, time::Duration};
+use std::{fmt, num::NonZeroU8, time::Duration};
 
 use bitflags::bitflags;
+use rusb::Speed;
 use uuid::Uuid;
 
-use crate::util::BcdVersion;
+use crate::{
+    formats::PixelFormat,
+    util::{BcdVersion, FormatGuid},
+};
+
+/// A spec violation or oddity encountered while parsing a device's descriptors.
+///
+/// These don't prevent the device from being used (the parser recovers from them), but they
+/// indicate bugs in the device firmware. They are also logged when encountered.
+#[derive(Debug, Clone)]
+pub struct ParseWarning {
+    message: String,
+}
+
+impl ParseWarning {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
 
 /// Identifies a video data source (either a [`Unit`], or an [`InputTerminal`]).
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct SourceId(NonZeroU8);
 
 impl SourceId {
@@ -27,7 +52,7 @@ impl SourceId {
 }
 
 /// Identifies an [`InputTerminal`] or an [`OutputTerminal`].
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct TermId(NonZeroU8);
 
 impl TermId {
@@ -80,6 +105,12 @@ impl From<ProcessingUnitId> for UnitId {
 #[derive(Clone, Copy, Debug)]
 pub struct SelectorUnitId(UnitId);
 
+impl SelectorUnitId {
+    pub(crate) fn as_raw(self) -> u8 {
+        self.0 .0.into()
+    }
+}
+
 impl From<SelectorUnitId> for UnitId {
     fn from(id: SelectorUnitId) -> Self {
         id.0
@@ -89,12 +120,42 @@ impl From<SelectorUnitId> for UnitId {
 #[derive(Clone, Copy, Debug)]
 pub struct ExtensionUnitId(UnitId);
 
+impl ExtensionUnitId {
+    pub(crate) fn as_raw(self) -> u8 {
+        self.0 .0.into()
+    }
+}
+
 impl From<ExtensionUnitId> for UnitId {
     fn from(id: ExtensionUnitId) -> Self {
         id.0
     }
 }
 
+/// Implements `Display` for ID newtypes by printing just the numeric ID.
+macro_rules! display_numeric_id {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl fmt::Display for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+        )+
+    };
+}
+
+display_numeric_id!(
+    SourceId,
+    TermId,
+    CameraId,
+    UnitId,
+    ProcessingUnitId,
+    SelectorUnitId,
+    ExtensionUnitId,
+    StreamingInterfaceId,
+);
+
 /// The device topology as reported by the Video Control interface descriptors.
 #[derive(Debug)]
 pub struct Topology {
@@ -102,9 +163,28 @@ pub struct Topology {
     units: Vec<UnitDesc>,
     inputs: Vec<InputTerminalDesc>,
     outputs: Vec<OutputTerminalDesc>,
+    unknown_descriptors: Vec<(u8, Vec<u8>)>,
 }
 
 impl Topology {
+    /// Parses a device topology from the raw class-specific Video Control interface descriptors.
+    ///
+    /// `control_extra` is the descriptor data that follows the standard Video Control interface
+    /// descriptor (starting with the `VC_HEADER` descriptor), for example taken from a raw
+    /// configuration descriptor dump. This allows inspecting devices without having access to
+    /// them.
+    pub fn parse_from_bytes(control_extra: &[u8]) -> crate::Result<Topology> {
+        parse::parse_control_extra(
+            control_extra,
+            parse::DEFAULT_REPAIR_PADDING,
+            &parse::ParseWarnings::default(),
+        )
+    }
+
+    pub fn header(&self) -> &ControlHeader {
+        &self.header
+    }
+
     pub fn camera_terminal_by_id(&self, id: CameraId) -> &CameraTerminalDesc {
         self.inputs
             .iter()
@@ -121,6 +201,22 @@ impl Topology {
             .expect("could not find processing unit in device topology")
     }
 
+    pub fn selector_unit_by_id(&self, id: SelectorUnitId) -> &SelectorUnitDesc {
+        self.units
+            .iter()
+            .filter_map(|unit| unit.as_selector_unit())
+            .find(|unit| unit.id.0 .0 == id.0 .0)
+            .expect("could not find selector unit in device topology")
+    }
+
+    pub fn extension_unit_by_id(&self, id: ExtensionUnitId) -> &ExtensionUnitDesc {
+        self.units
+            .iter()
+            .filter_map(|unit| unit.as_extension_unit())
+            .find(|unit| unit.id.0 .0 == id.0 .0)
+            .expect("could not find extension unit in device topology")
+    }
+
     pub fn units(&self) -> &[UnitDesc] {
         &self.units
     }
@@ -132,6 +228,14 @@ impl Topology {
     pub fn outputs(&self) -> &[OutputTerminalDesc] {
         &self.outputs
     }
+
+    /// Returns the descriptors of the Video Control interface that were not recognized.
+    ///
+    /// Each entry consists of the `bDescriptorType` and the raw descriptor bytes (including the
+    /// `bLength` and `bDescriptorType` fields). These are typically vendor-specific.
+    pub fn unknown_descriptors(&self) -> &[(u8, Vec<u8>)] {
+        &self.unknown_descriptors
+    }
 }
 
 #[derive(Debug)]
@@ -142,6 +246,22 @@ pub struct ControlHeader {
     streaming_interfaces: Vec<u8>,
 }
 
+impl ControlHeader {
+    /// Returns the UVC specification version the device implements (`bcdUVC`).
+    pub fn uvc_version(&self) -> BcdVersion {
+        self.uvc_version
+    }
+
+    /// Returns the device clock frequency in Hz (`dwClockFrequency`).
+    ///
+    /// This is 0 on some devices, and deprecated as of UVC 1.5. Use
+    /// [`UvcDevice::clock_frequency`][crate::UvcDevice::clock_frequency] for a value that falls
+    /// back to the one reported during stream negotiation.
+    pub fn clock_frequency(&self) -> u32 {
+        self.clock_freq_hz
+    }
+}
+
 /// A unit declared by the Video Control Interface Descriptors.
 #[derive(Debug)]
 pub struct UnitDesc {
@@ -153,12 +273,26 @@ impl UnitDesc {
         &self.kind
     }
 
+    pub fn as_selector_unit(&self) -> Option<&SelectorUnitDesc> {
+        match &self.kind {
+            UnitKind::Selector(unit) => Some(unit),
+            _ => None,
+        }
+    }
+
     pub fn as_processing_unit(&self) -> Option<&ProcessingUnitDesc> {
         match &self.kind {
             UnitKind::Processing(unit) => Some(unit),
             _ => None,
         }
     }
+
+    pub fn as_extension_unit(&self) -> Option<&ExtensionUnitDesc> {
+        match &self.kind {
+            UnitKind::Extension(unit) => Some(unit),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -175,12 +309,24 @@ pub struct SelectorUnitDesc {
     inputs: Vec<SourceId>,
 }
 
+impl SelectorUnitDesc {
+    pub fn id(&self) -> SelectorUnitId {
+        self.id
+    }
+
+    /// Returns the sources this unit can select between, in input pin order.
+    pub fn inputs(&self) -> &[SourceId] {
+        &self.inputs
+    }
+}
+
 #[derive(Debug)]
 pub struct ProcessingUnitDesc {
     id: ProcessingUnitId,
     source: SourceId,
     max_multiplier: u16,
     controls: ProcessingUnitControls,
+    raw_controls: Vec<u8>,
     string: u8,
     standards: VideoStandards,
 }
@@ -193,6 +339,14 @@ impl ProcessingUnitDesc {
     pub fn controls(&self) -> ProcessingUnitControls {
         self.controls
     }
+
+    /// Returns the unit's complete `bmControls` bitmap, in the order it appears in the descriptor.
+    ///
+    /// Unlike [`ProcessingUnitDesc::controls`], this includes bits that are not (yet) known to this
+    /// library.
+    pub fn raw_controls_bitmap(&self) -> &[u8] {
+        &self.raw_controls
+    }
 }
 
 bitflags! {
@@ -239,6 +393,34 @@ pub struct ExtensionUnitDesc {
     controls_bitmap: Vec<u8>,
 }
 
+impl ExtensionUnitDesc {
+    pub fn id(&self) -> ExtensionUnitId {
+        self.id
+    }
+
+    /// Returns the GUID identifying the vendor-specific extension (`guidExtensionCode`).
+    pub fn extension_code(&self) -> Uuid {
+        self.extension_code
+    }
+
+    /// Returns the number of controls the unit implements (`bNumControls`).
+    pub fn num_controls(&self) -> u8 {
+        self.num_controls
+    }
+
+    /// Returns the sources this unit receives data from, in input pin order.
+    pub fn inputs(&self) -> &[SourceId] {
+        &self.inputs
+    }
+
+    /// Returns the unit's `bmControls` bitmap, in the order it appears in the descriptor.
+    ///
+    /// The meaning of the bits is vendor-specific.
+    pub fn raw_controls_bitmap(&self) -> &[u8] {
+        &self.controls_bitmap
+    }
+}
+
 #[derive(Debug)]
 pub struct OutputTerminalDesc {
     term_id: TermId,
@@ -249,6 +431,15 @@ pub struct OutputTerminalDesc {
 }
 
 impl OutputTerminalDesc {
+    pub fn id(&self) -> TermId {
+        self.term_id
+    }
+
+    /// Returns the ID of the unit or terminal this terminal receives its data from.
+    pub fn source(&self) -> SourceId {
+        self.source
+    }
+
     pub fn terminal_type(&self) -> Option<OutputTerminalType> {
         OutputTerminalType::from_raw(self.term_type)
     }
@@ -264,6 +455,10 @@ pub struct InputTerminalDesc {
 }
 
 impl InputTerminalDesc {
+    pub fn id(&self) -> TermId {
+        self.term_id
+    }
+
     pub fn terminal_type(&self) -> Option<InputTerminalType> {
         InputTerminalType::from_raw(self.term_type)
     }
@@ -301,12 +496,22 @@ pub struct CameraTerminalDesc {
     objective_focal_length_max: u16,
     ocular_focal_length: u16,
     controls: CameraControls,
+    raw_controls: Vec<u8>,
 }
 
 impl CameraTerminalDesc {
     pub fn controls(&self) -> CameraControls {
         self.controls
     }
+
+    /// Returns the terminal's complete `bmControls` bitmap, in the order it appears in the
+    /// descriptor.
+    ///
+    /// Unlike [`CameraTerminalDesc::controls`], this includes bits that are not (yet) known to this
+    /// library.
+    pub fn raw_controls_bitmap(&self) -> &[u8] {
+        &self.raw_controls
+    }
 }
 
 bitflags! {
@@ -378,9 +583,32 @@ pub struct StreamingInterfaceDesc {
     kind: StreamingInterfaceKind,
     formats: Vec<Format>,
     frames: Vec<Frame>,
+    still_image_frames: Vec<StillImageFrame>,
+    unknown_descriptors: Vec<(u8, Vec<u8>)>,
+    alt_settings: Vec<AltSetting>,
 }
 
 impl StreamingInterfaceDesc {
+    /// Parses a Video Streaming interface from its raw class-specific descriptors.
+    ///
+    /// `extra` is the descriptor data that follows the standard interface descriptor of the
+    /// Video Streaming interface `interface_number` (starting with its input or output header).
+    /// Since the alternate settings are not part of that data, [`alt_settings`] of the returned
+    /// value will be empty.
+    ///
+    /// [`alt_settings`]: StreamingInterfaceDesc::alt_settings
+    pub fn parse_from_bytes(
+        interface_number: u8,
+        extra: &[u8],
+    ) -> crate::Result<StreamingInterfaceDesc> {
+        parse::parse_streaming_extra(
+            interface_number,
+            extra,
+            parse::DEFAULT_REPAIR_PADDING,
+            &parse::ParseWarnings::default(),
+        )
+    }
+
     pub fn id(&self) -> StreamingInterfaceId {
         self.id
     }
@@ -393,14 +621,258 @@ impl StreamingInterfaceDesc {
         &self.frames
     }
 
+    /// Returns the Still Image Frame descriptors of this interface.
+    ///
+    /// These are only present on interfaces using still image capture method 2 or 3.
+    pub fn still_image_frames(&self) -> &[StillImageFrame] {
+        &self.still_image_frames
+    }
+
+    /// Returns the address of the dedicated still image endpoint used by capture method 3.
+    ///
+    /// Returns `None` if the interface doesn't have one, in which case still images (if
+    /// supported) are transferred over the video endpoint.
+    pub fn still_endpoint_address(&self) -> Option<u8> {
+        self.still_image_frames
+            .iter()
+            .find_map(|f| f.endpoint_address())
+    }
+
+    /// Returns the sorted, de-duplicated `(width, height)` pairs of all frames of all formats.
+    pub fn supported_resolutions(&self) -> Vec<(u16, u16)> {
+        let mut resolutions = self
+            .frames
+            .iter()
+            .map(Frame::resolution)
+            .collect::<Vec<_>>();
+        resolutions.sort_unstable();
+        resolutions.dedup();
+        resolutions
+    }
+
+    /// Returns the alternate settings of this interface.
+    ///
+    /// Isochronous interfaces have several alternate settings that differ in the bandwidth they
+    /// reserve on the bus. Bulk interfaces typically only have alternate setting 0.
+    pub fn alt_settings(&self) -> &[AltSetting] {
+        &self.alt_settings
+    }
+
+    /// Returns the descriptors of this Video Streaming interface that were not recognized.
+    ///
+    /// Each entry consists of the `bDescriptorType` and the raw descriptor bytes (including the
+    /// `bLength` and `bDescriptorType` fields). These are typically vendor-specific.
+    pub fn unknown_descriptors(&self) -> &[(u8, Vec<u8>)] {
+        &self.unknown_descriptors
+    }
+
     pub fn frame_by_index(&self, index: FrameIndex) -> &Frame {
         self.frames.iter().find(|f| f.index().0 == index.0).unwrap()
     }
 
+    /// Returns the first uncompressed format with the pixel format `pixel_format`, or `None` if the
+    /// interface doesn't support it.
+    pub fn format_by_pixel_format(&self, pixel_format: PixelFormat) -> Option<&Format> {
+        self.formats.iter().find(|f| {
+            f.as_format_uncompressed()
+                .and_then(FormatUncompressed::pixel_format)
+                == Some(pixel_format)
+        })
+    }
+
+    /// Returns the format with index `format`, or `None` if there is no such format.
+    pub fn try_format_by_index(&self, format: FormatIndex) -> Option<&Format> {
+        self.formats.iter().find(|f| f.index().0 == format.0)
+    }
+
+    /// Returns the frame with index `frame` of the format `format`, or `None` if that format has
+    /// no such frame.
+    pub fn try_frame_by_index(&self, format: FormatIndex, frame: FrameIndex) -> Option<&Frame> {
+        self.frames
+            .iter()
+            .find(|f| f.format_index().0 == format.0 && f.index().0 == frame.0)
+    }
+
+    /// Returns the runtime controls supported by the format with the given index.
+    ///
+    /// Returns `None` if `format` does not exist.
+    pub fn format_controls(&self, format: FormatIndex) -> Option<PerFormatControls> {
+        let controls = match &self.kind {
+            StreamingInterfaceKind::Input(k) => &k.format_controls,
+            StreamingInterfaceKind::Output(k) => &k.format_controls,
+        };
+
+        // Format indices are 1-based.
+        let index = usize::from(format.0).checked_sub(1)?;
+        controls.get(index).copied()
+    }
+
+    /// Returns the still image capture method of an input interface, or `None` for output
+    /// interfaces.
+    pub fn still_capture_method(&self) -> Option<StillCaptureMethod> {
+        match &self.kind {
+            StreamingInterfaceKind::Input(k) => Some(k.still_capture_method),
+            StreamingInterfaceKind::Output(_) => None,
+        }
+    }
+
+    /// Returns the capabilities of an input interface, or `None` for output interfaces.
+    pub fn input_info(&self) -> Option<InputInterfaceInfo> {
+        match &self.kind {
+            StreamingInterfaceKind::Input(k) => Some(k.info),
+            StreamingInterfaceKind::Output(_) => None,
+        }
+    }
+
+    /// Returns the ID of the terminal this interface is connected to.
+    ///
+    /// For input interfaces, this is the [`OutputTerminalDesc`] the video data is streamed from.
+    /// For output interfaces, it is the [`InputTerminalDesc`] receiving the video data. Following
+    /// the sources of the terminal through the [`Topology`] leads to the units and terminals that
+    /// affect the stream.
+    pub fn terminal_link(&self) -> TermId {
+        match &self.kind {
+            StreamingInterfaceKind::Input(k) => k.terminal_link,
+            StreamingInterfaceKind::Output(k) => k.terminal_link,
+        }
+    }
+
     pub fn endpoint_address(&self) -> u8 {
         match &self.kind {
             StreamingInterfaceKind::Input(k) => k.endpoint_address,
-            StreamingInterfaceKind::Output(_) => todo!(),
+            StreamingInterfaceKind::Output(k) => k.endpoint_address,
+        }
+    }
+
+    /// Returns the direction of the interface's data endpoint.
+    ///
+    /// This is [`EndpointDirection::In`] for input interfaces and [`EndpointDirection::Out`] for
+    /// output interfaces (this is checked when parsing the descriptors).
+    pub fn endpoint_direction(&self) -> EndpointDirection {
+        EndpointDirection::from_address(self.endpoint_address())
+    }
+}
+
+/// Describes the still images a format supports (Still Image Frame descriptor).
+#[derive(Debug)]
+pub struct StillImageFrame {
+    format_index: FormatIndex,
+    endpoint_address: Option<u8>,
+    image_sizes: Vec<(u16, u16)>,
+    compressions: Vec<u8>,
+}
+
+impl StillImageFrame {
+    /// Returns the index of the format this descriptor belongs to.
+    pub fn format_index(&self) -> FormatIndex {
+        self.format_index
+    }
+
+    /// Returns the address of the bulk endpoint still images are transferred over.
+    ///
+    /// This is only set for still image capture method 3. With method 2, still images are sent
+    /// over the video endpoint instead.
+    pub fn endpoint_address(&self) -> Option<u8> {
+        self.endpoint_address
+    }
+
+    /// Returns the supported still image sizes as `(width, height)` pairs.
+    pub fn image_sizes(&self) -> &[(u16, u16)] {
+        &self.image_sizes
+    }
+
+    /// Returns the supported compression ratios (`bCompression`) for still images.
+    pub fn compressions(&self) -> &[u8] {
+        &self.compressions
+    }
+}
+
+/// An alternate setting of a Video Streaming interface.
+#[derive(Debug, Clone, Copy)]
+pub struct AltSetting {
+    number: u8,
+    endpoint_address: Option<u8>,
+    max_packet_size: u16,
+    interval: u8,
+    has_still_endpoint: bool,
+}
+
+impl AltSetting {
+    /// Returns the alternate setting number (`bAlternateSetting`).
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    /// Returns the address of the alternate setting's video endpoint, if it has one.
+    ///
+    /// Alternate setting 0 of isochronous interfaces has no video endpoint, so that it doesn't
+    /// reserve any bandwidth.
+    pub fn endpoint_address(&self) -> Option<u8> {
+        self.endpoint_address
+    }
+
+    /// Returns whether the alternate setting contains the interface's still image endpoint (see
+    /// [`StreamingInterfaceDesc::still_endpoint_address`]).
+    pub fn has_still_endpoint(&self) -> bool {
+        self.has_still_endpoint
+    }
+
+    /// Returns the raw `wMaxPacketSize` of the alternate setting's endpoint, or 0 if there is no
+    /// endpoint.
+    pub fn max_packet_size(&self) -> u16 {
+        self.max_packet_size
+    }
+
+    /// Returns the number of Bytes the endpoint can transfer per service interval.
+    ///
+    /// For high-speed isochronous endpoints, this includes the additional transactions per
+    /// microframe encoded in bits 11 and 12 of `wMaxPacketSize`. SuperSpeed burst information is
+    /// not taken into account.
+    pub fn bytes_per_interval(&self) -> u32 {
+        let size = u32::from(self.max_packet_size & 0x7ff);
+        let transactions = u32::from((self.max_packet_size >> 11) & 0b11) + 1;
+        size * transactions
+    }
+
+    /// Returns the raw `bInterval` of the alternate setting's endpoint, or 0 if there is no
+    /// endpoint.
+    pub fn interval(&self) -> u8 {
+        self.interval
+    }
+
+    /// Returns the time between two service intervals of the isochronous endpoint, for a device
+    /// connected at `speed`.
+    ///
+    /// The endpoint is serviced every 2<sup>`bInterval`-1</sup> frames (1 ms) at full speed, and
+    /// every 2<sup>`bInterval`-1</sup> microframes (125 µs) at high speed and above. Returns
+    /// `None` if there is no endpoint.
+    pub fn service_interval(&self, speed: Speed) -> Option<Duration> {
+        self.endpoint_address?;
+        let unit = match speed {
+            Speed::Low | Speed::Full => Duration::from_millis(1),
+            _ => Duration::from_micros(125),
+        };
+        // Valid values are 1 to 16.
+        let exponent = self.interval.clamp(1, 16) - 1;
+        Some(unit * (1 << exponent))
+    }
+}
+
+/// Direction of a USB endpoint, as seen from the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointDirection {
+    /// Device-to-host.
+    In,
+    /// Host-to-device.
+    Out,
+}
+
+impl EndpointDirection {
+    pub(crate) fn from_address(address: u8) -> Self {
+        if address & 0x80 != 0 {
+            Self::In
+        } else {
+            Self::Out
         }
     }
 }
@@ -425,7 +897,11 @@ pub struct InputHeader {
 }
 
 #[derive(Debug)]
-pub struct OutputHeader {}
+pub struct OutputHeader {
+    endpoint_address: u8,
+    terminal_link: TermId,
+    format_controls: Vec<PerFormatControls>,
+}
 
 bitflags! {
     pub struct InputInterfaceInfo: u8 {
@@ -478,6 +954,7 @@ pub struct FrameIndex(pub(crate) u8);
 pub struct Format {
     format_index: FormatIndex,
     num_frame_descriptors: u8,
+    color_matching: Option<ColorMatching>,
     kind: FormatKind,
 }
 
@@ -485,15 +962,218 @@ impl Format {
     pub fn index(&self) -> FormatIndex {
         self.format_index
     }
+
+    /// Returns the color matching information the device specified for this format.
+    ///
+    /// If this returns `None`, the defaults from [`ColorMatching::default`] apply.
+    pub fn color_matching(&self) -> Option<&ColorMatching> {
+        self.color_matching.as_ref()
+    }
+
+    /// Returns a human-readable name of the format's GUID.
+    ///
+    /// This is the FourCC code stored in the GUID (eg. `YUY2`) if it is printable, and the full
+    /// GUID otherwise. Returns `None` for formats that aren't identified by a GUID.
+    pub fn format_name(&self) -> Option<String> {
+        match &self.kind {
+            FormatKind::Uncompressed(f) => Some(FormatGuid(&f.format).to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn as_format_uncompressed(&self) -> Option<&FormatUncompressed> {
+        match &self.kind {
+            FormatKind::Uncompressed(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_format_mjpeg(&self) -> Option<&FormatMjpeg> {
+        match &self.kind {
+            FormatKind::Mjpeg(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_format_mpeg2ts(&self) -> Option<&FormatMpeg2Ts> {
+        match &self.kind {
+            FormatKind::Mpeg2Ts(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_format_dv(&self) -> Option<&FormatDv> {
+        match &self.kind {
+            FormatKind::Dv(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_format_h264(&self) -> Option<&FormatH264> {
+        match &self.kind {
+            FormatKind::H264(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_format_vp8(&self) -> Option<&FormatVp8> {
+        match &self.kind {
+            FormatKind::Vp8(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+/// Color space information of a format, from the Color Matching descriptor.
+///
+/// Values the descriptor doesn't define are replaced with BT.709 when parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorMatching {
+    color_primaries: ColorPrimaries,
+    transfer_characteristics: TransferCharacteristics,
+    matrix_coefficients: MatrixCoefficients,
+}
+
+impl ColorMatching {
+    pub fn color_primaries(&self) -> ColorPrimaries {
+        self.color_primaries
+    }
+
+    pub fn transfer_characteristics(&self) -> TransferCharacteristics {
+        self.transfer_characteristics
+    }
+
+    pub fn matrix_coefficients(&self) -> MatrixCoefficients {
+        self.matrix_coefficients
+    }
+}
+
+impl Default for ColorMatching {
+    /// Returns the values the UVC specification mandates when no Color Matching descriptor is
+    /// present (BT.709 primaries and transfer characteristics, SMPTE 170M matrix coefficients).
+    fn default() -> Self {
+        Self {
+            color_primaries: ColorPrimaries::Bt709,
+            transfer_characteristics: TransferCharacteristics::Bt709,
+            matrix_coefficients: MatrixCoefficients::Smpte170M,
+        }
+    }
+}
+
+primitive_enum! {
+    /// Chromaticity coordinates of the source primaries (`bColorPrimaries`).
+    pub enum ColorPrimaries: u8 {
+        Unspecified = 0,
+        /// BT.709 and sRGB.
+        Bt709 = 1,
+        /// BT.470-2 (M).
+        Bt470_2M = 2,
+        /// BT.470-2 (B, G).
+        Bt470_2BG = 3,
+        Smpte170M = 4,
+        Smpte240M = 5,
+    }
+}
+
+primitive_enum! {
+    /// Opto-electronic transfer characteristic of the source picture (`bTransferCharacteristics`).
+    pub enum TransferCharacteristics: u8 {
+        Unspecified = 0,
+        Bt709 = 1,
+        /// BT.470-2 (M).
+        Bt470_2M = 2,
+        /// BT.470-2 (B, G).
+        Bt470_2BG = 3,
+        Smpte170M = 4,
+        Smpte240M = 5,
+        Linear = 6,
+        Srgb = 7,
+    }
+}
+
+primitive_enum! {
+    /// Matrix used to compute luma and chroma from the color primaries (`bMatrixCoefficients`).
+    pub enum MatrixCoefficients: u8 {
+        Unspecified = 0,
+        Bt709 = 1,
+        Fcc = 2,
+        /// BT.470-2 (B, G).
+        Bt470_2BG = 3,
+        /// SMPTE 170M (BT.601).
+        Smpte170M = 4,
+        Smpte240M = 5,
+    }
 }
 
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum FormatKind {
     Uncompressed(FormatUncompressed),
+    Mjpeg(FormatMjpeg),
+    Mpeg2Ts(FormatMpeg2Ts),
+    Dv(FormatDv),
+    H264(FormatH264),
+    Vp8(FormatVp8),
 }
 
+/// MPEG-2 Transport Stream format.
+///
+/// This is a stream-based format, so it has no frame descriptors.
 #[derive(Debug)]
+pub struct FormatMpeg2Ts {
+    data_offset: u8,
+    packet_length: u8,
+    stride_length: u8,
+    stride_format: Option<Uuid>,
+}
+
+impl FormatMpeg2Ts {
+    /// Returns the offset (in Bytes) from the start of each TS packet to its data.
+    pub fn data_offset(&self) -> u8 {
+        self.data_offset
+    }
+
+    /// Returns the length of a TS packet in Bytes (typically 188).
+    pub fn packet_length(&self) -> u8 {
+        self.packet_length
+    }
+
+    /// Returns the length of a stride in Bytes.
+    pub fn stride_length(&self) -> u8 {
+        self.stride_length
+    }
+
+    /// Returns the GUID identifying the stride format.
+    ///
+    /// Only present on devices implementing UVC 1.1 or later.
+    pub fn stride_format(&self) -> Option<Uuid> {
+        self.stride_format
+    }
+}
+
+/// DV format.
+///
+/// This is a stream-based format, so it has no frame descriptors.
+#[derive(Debug)]
+pub struct FormatDv {
+    max_video_frame_buffer_size: u32,
+    format_type: u8,
+}
+
+impl FormatDv {
+    pub fn max_video_frame_buffer_size(&self) -> u32 {
+        self.max_video_frame_buffer_size
+    }
+
+    /// Returns the raw `bFormatType` value.
+    ///
+    /// Bits 0-6 select the DV variant (0 = SD-DV, 1 = SDL-DV, 2 = HD-DV), bit 7 is 0 for 50 Hz
+    /// and 1 for 60 Hz video.
+    pub fn format_type(&self) -> u8 {
+        self.format_type
+    }
+}
+
 pub struct FormatUncompressed {
     format: Uuid,
     bits_per_pixel: u8,
@@ -504,6 +1184,191 @@ pub struct FormatUncompressed {
     copy_protect: u8, // cute
 }
 
+impl fmt::Debug for FormatUncompressed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FormatUncompressed")
+            .field("format", &FormatGuid(&self.format))
+            .field("bits_per_pixel", &self.bits_per_pixel)
+            .field("default_frame_index", &self.default_frame_index)
+            .field("aspect_ratio_x", &self.aspect_ratio_x)
+            .field("aspect_ratio_y", &self.aspect_ratio_y)
+            .field("interlace_flags", &self.interlace_flags)
+            .field("copy_protect", &self.copy_protect)
+            .finish()
+    }
+}
+
+impl FormatUncompressed {
+    /// Returns the GUID identifying the format.
+    ///
+    /// See the [`formats`][crate::formats] module for well-known values.
+    pub fn format(&self) -> Uuid {
+        self.format
+    }
+
+    /// Returns the pixel format identified by the format GUID.
+    ///
+    /// Returns `None` if the GUID isn't a known uncompressed format, in which case
+    /// [`FormatUncompressed::format`] can be inspected directly.
+    pub fn pixel_format(&self) -> Option<PixelFormat> {
+        PixelFormat::from_guid(&self.format)
+    }
+
+    pub fn default_frame_index(&self) -> FrameIndex {
+        self.default_frame_index
+    }
+
+    pub fn interlace_flags(&self) -> InterlaceFlags {
+        self.interlace_flags
+    }
+
+    /// Returns the raw `bCopyProtect` value.
+    ///
+    /// A value of 0 means that there are no restrictions, 1 means that duplication of the video
+    /// stream should be restricted.
+    pub fn copy_protect(&self) -> u8 {
+        self.copy_protect
+    }
+}
+
+/// Motion-JPEG format.
+#[derive(Debug)]
+pub struct FormatMjpeg {
+    flags: MjpegFormatFlags,
+    default_frame_index: FrameIndex,
+    aspect_ratio_x: u8,
+    aspect_ratio_y: u8,
+    interlace_flags: InterlaceFlags,
+    copy_protect: u8,
+}
+
+impl FormatMjpeg {
+    pub fn flags(&self) -> MjpegFormatFlags {
+        self.flags
+    }
+
+    pub fn default_frame_index(&self) -> FrameIndex {
+        self.default_frame_index
+    }
+
+    /// Returns the picture aspect ratio as `(x, y)`.
+    ///
+    /// Both values are 0 if the aspect ratio isn't specified (non-interlaced streams).
+    pub fn aspect_ratio(&self) -> (u8, u8) {
+        (self.aspect_ratio_x, self.aspect_ratio_y)
+    }
+
+    pub fn interlace_flags(&self) -> InterlaceFlags {
+        self.interlace_flags
+    }
+
+    /// Returns the raw `bCopyProtect` value.
+    ///
+    /// A value of 0 means that there are no restrictions, 1 means that duplication of the video
+    /// stream should be restricted.
+    pub fn copy_protect(&self) -> u8 {
+        self.copy_protect
+    }
+}
+
+bitflags! {
+    pub struct MjpegFormatFlags: u8 {
+        const FIXED_SIZE_SAMPLES = 1 << 0;
+    }
+}
+
+/// H.264 format (UVC 1.5).
+#[derive(Debug)]
+pub struct FormatH264 {
+    default_frame_index: FrameIndex,
+    max_codec_config_delay: u8,
+    supported_slice_modes: u8,
+    supported_sync_frame_types: u8,
+    resolution_scaling: u8,
+    supported_rate_control_modes: u8,
+}
+
+impl FormatH264 {
+    pub fn default_frame_index(&self) -> FrameIndex {
+        self.default_frame_index
+    }
+
+    /// Returns the maximum number of frames the encoder takes to respond to a configuration
+    /// change.
+    pub fn max_codec_config_delay(&self) -> u8 {
+        self.max_codec_config_delay
+    }
+
+    /// Returns the raw `bmSupportedSliceModes` bitmap.
+    pub fn supported_slice_modes(&self) -> u8 {
+        self.supported_slice_modes
+    }
+
+    /// Returns the raw `bmSupportedSyncFrameTypes` bitmap.
+    pub fn supported_sync_frame_types(&self) -> u8 {
+        self.supported_sync_frame_types
+    }
+
+    /// Returns the raw `bResolutionScaling` value.
+    pub fn resolution_scaling(&self) -> u8 {
+        self.resolution_scaling
+    }
+
+    /// Returns the raw `bmSupportedRateControlModes` bitmap.
+    pub fn supported_rate_control_modes(&self) -> u8 {
+        self.supported_rate_control_modes
+    }
+}
+
+/// VP8 format (UVC 1.5).
+#[derive(Debug)]
+pub struct FormatVp8 {
+    default_frame_index: FrameIndex,
+    max_codec_config_delay: u8,
+    supported_partition_count: u8,
+    supported_sync_frame_types: u8,
+    resolution_scaling: u8,
+    supported_rate_control_modes: u8,
+    max_mb_per_sec: u16,
+}
+
+impl FormatVp8 {
+    pub fn default_frame_index(&self) -> FrameIndex {
+        self.default_frame_index
+    }
+
+    /// Returns the maximum number of frames the encoder takes to respond to a configuration
+    /// change.
+    pub fn max_codec_config_delay(&self) -> u8 {
+        self.max_codec_config_delay
+    }
+
+    /// Returns the raw `bmSupportedPartitionCount` bitmap.
+    pub fn supported_partition_count(&self) -> u8 {
+        self.supported_partition_count
+    }
+
+    /// Returns the raw `bmSupportedSyncFrameTypes` bitmap.
+    pub fn supported_sync_frame_types(&self) -> u8 {
+        self.supported_sync_frame_types
+    }
+
+    /// Returns the raw `bResolutionScaling` value.
+    pub fn resolution_scaling(&self) -> u8 {
+        self.resolution_scaling
+    }
+
+    /// Returns the raw `bmSupportedRateControlModes` bitmap.
+    pub fn supported_rate_control_modes(&self) -> u8 {
+        self.supported_rate_control_modes
+    }
+
+    /// Returns the maximum macroblock processing rate in macroblocks per second.
+    pub fn max_mb_per_sec(&self) -> u16 {
+        self.max_mb_per_sec
+    }
+}
+
 bitflags! {
     pub struct InterlaceFlags: u8 {
         const INTERLACED = 1 << 0;
@@ -513,8 +1378,32 @@ bitflags! {
     }
 }
 
+impl InterlaceFlags {
+    /// Returns the field pattern encoded in the [`InterlaceFlags::FIELD_PATTERN_MASK`] bits.
+    pub fn field_pattern(&self) -> FieldPattern {
+        // unwrap: all 2-bit values are valid patterns
+        FieldPattern::from_raw((self.bits() & Self::FIELD_PATTERN_MASK.bits()) >> 4).unwrap()
+    }
+}
+
+primitive_enum! {
+    /// The pattern of fields in an interlaced stream.
+    pub enum FieldPattern: u8 {
+        /// Only field 1 is transmitted.
+        Field1Only = 0b00,
+        /// Only field 2 is transmitted.
+        Field2Only = 0b01,
+        /// Fields 1 and 2 alternate in a regular pattern.
+        Regular = 0b10,
+        /// Fields 1 and 2 are transmitted in a random pattern.
+        Random = 0b11,
+    }
+}
+
 #[derive(Debug)]
 pub struct Frame {
+    /// Index of the format descriptor preceding this frame descriptor.
+    format_index: FormatIndex,
     frame_index: FrameIndex,
     kind: FrameKind,
 }
@@ -524,17 +1413,118 @@ impl Frame {
         self.frame_index
     }
 
+    /// Returns the index of the format this frame belongs to.
+    ///
+    /// Frame indices are only unique within a format, so a frame is identified by both indices.
+    pub fn format_index(&self) -> FormatIndex {
+        self.format_index
+    }
+
     pub fn as_frame_uncompressed(&self) -> Option<&FrameUncompressed> {
         match &self.kind {
             FrameKind::Uncompressed(f) => Some(f),
+            _ => None,
         }
     }
+
+    pub fn as_frame_mjpeg(&self) -> Option<&FrameMjpeg> {
+        match &self.kind {
+            FrameKind::Mjpeg(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_frame_h264(&self) -> Option<&FrameH264> {
+        match &self.kind {
+            FrameKind::H264(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_frame_vp8(&self) -> Option<&FrameVp8> {
+        match &self.kind {
+            FrameKind::Vp8(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    /// Returns the `(width, height)` of this frame in pixels.
+    pub fn resolution(&self) -> (u16, u16) {
+        match &self.kind {
+            FrameKind::Uncompressed(f) => (f.width(), f.height()),
+            FrameKind::Mjpeg(f) => (f.width(), f.height()),
+            FrameKind::H264(f) => (f.width(), f.height()),
+            FrameKind::Vp8(f) => (f.width(), f.height()),
+        }
+    }
+
+    pub fn default_frame_interval(&self) -> Duration {
+        match &self.kind {
+            FrameKind::Uncompressed(f) => f.default_frame_interval(),
+            FrameKind::Mjpeg(f) => f.default_frame_interval(),
+            FrameKind::H264(f) => f.default_frame_interval(),
+            FrameKind::Vp8(f) => f.default_frame_interval(),
+        }
+    }
+
+    pub fn frame_intervals(&self) -> &SupportedFrameIntervals {
+        match &self.kind {
+            FrameKind::Uncompressed(f) => &f.frame_interval,
+            FrameKind::Mjpeg(f) => f.frame_intervals(),
+            FrameKind::H264(f) => f.frame_intervals(),
+            FrameKind::Vp8(f) => f.frame_intervals(),
+        }
+    }
+
+    /// Returns the supported frame rates in frames per second, in descending order.
+    ///
+    /// The rates are rounded to two decimal places and de-duplicated. For continuous frame
+    /// intervals, every step between the minimum and maximum interval is included (up to a limit
+    /// of 1000 values, after which the maximum interval is added as the last value).
+    pub fn supported_fps(&self) -> Vec<f64> {
+        const MAX_STEPS: usize = 1000;
+
+        let intervals = match self.frame_intervals() {
+            SupportedFrameIntervals::Discrete {
+                supported_frame_intervals,
+            } => supported_frame_intervals.clone(),
+            SupportedFrameIntervals::Continuous {
+                min_frame_interval,
+                max_frame_interval,
+                frame_interval_step,
+            } => {
+                let mut intervals = Vec::new();
+                let mut interval = *min_frame_interval;
+                while interval < *max_frame_interval
+                    && intervals.len() < MAX_STEPS
+                    && !frame_interval_step.is_zero()
+                {
+                    intervals.push(interval);
+                    interval += *frame_interval_step;
+                }
+                intervals.push(*max_frame_interval);
+                intervals
+            }
+        };
+
+        let mut fps = intervals
+            .into_iter()
+            .filter(|interval| !interval.is_zero())
+            .map(|interval| (100.0 / interval.as_secs_f64()).round() / 100.0)
+            .collect::<Vec<_>>();
+        fps.sort_by(|a, b| b.total_cmp(a));
+        fps.dedup();
+        fps
+    }
 }
 
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum FrameKind {
     Uncompressed(FrameUncompressed),
+    Mjpeg(FrameMjpeg),
+    H264(FrameH264),
+    Vp8(FrameVp8),
 }
 
 #[derive(Debug)]
@@ -550,9 +1540,46 @@ pub struct FrameUncompressed {
 }
 
 impl FrameUncompressed {
+    pub fn capabilities(&self) -> UncompressedFrameCapabilities {
+        self.capabilities
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the minimum bit rate in bits per second.
+    pub fn min_bit_rate(&self) -> u32 {
+        self.min_bit_rate
+    }
+
+    /// Returns the maximum bit rate in bits per second.
+    pub fn max_bit_rate(&self) -> u32 {
+        self.max_bit_rate
+    }
+
+    /// Returns the maximum size of a frame in Bytes.
+    ///
+    /// Deprecated by UVC 1.1 in favor of the `dwMaxVideoFrameSize` field of the probe/commit
+    /// controls.
+    pub fn max_video_frame_buffer_size(&self) -> u32 {
+        self.max_video_frame_buffer_size
+    }
+
     pub fn default_frame_interval(&self) -> Duration {
         self.default_frame_interval
     }
+
+    /// Returns the default frame rate in frames per second.
+    ///
+    /// This is derived from [`FrameUncompressed::default_frame_interval`].
+    pub fn default_frame_rate(&self) -> f32 {
+        1.0 / self.default_frame_interval.as_secs_f32()
+    }
 }
 
 bitflags! {
@@ -562,6 +1589,213 @@ bitflags! {
     }
 }
 
+/// Motion-JPEG frame.
+#[derive(Debug)]
+pub struct FrameMjpeg {
+    capabilities: MjpegFrameCapabilities,
+    width: u16,
+    height: u16,
+    min_bit_rate: u32,
+    max_bit_rate: u32,
+    max_video_frame_buffer_size: u32,
+    default_frame_interval: Duration,
+    frame_interval: SupportedFrameIntervals,
+}
+
+impl FrameMjpeg {
+    pub fn capabilities(&self) -> MjpegFrameCapabilities {
+        self.capabilities
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the minimum bit rate in bits per second.
+    pub fn min_bit_rate(&self) -> u32 {
+        self.min_bit_rate
+    }
+
+    /// Returns the maximum bit rate in bits per second.
+    pub fn max_bit_rate(&self) -> u32 {
+        self.max_bit_rate
+    }
+
+    /// Returns the maximum size of a compressed frame in Bytes.
+    ///
+    /// Deprecated by UVC 1.1 in favor of the `dwMaxVideoFrameSize` field of the probe/commit
+    /// controls.
+    pub fn max_video_frame_buffer_size(&self) -> u32 {
+        self.max_video_frame_buffer_size
+    }
+
+    pub fn default_frame_interval(&self) -> Duration {
+        self.default_frame_interval
+    }
+
+    pub fn frame_intervals(&self) -> &SupportedFrameIntervals {
+        &self.frame_interval
+    }
+}
+
+bitflags! {
+    pub struct MjpegFrameCapabilities: u8 {
+        const STILL_IMAGE_SUPPORTED = 1 << 0;
+        const FIXED_FRAME_RATE = 1 << 1;
+    }
+}
+
+/// H.264 frame (UVC 1.5).
+#[derive(Debug)]
+pub struct FrameH264 {
+    width: u16,
+    height: u16,
+    sar_width: u16,
+    sar_height: u16,
+    profile: u16,
+    level_idc: u8,
+    constrained_toolset: u16,
+    supported_usages: u32,
+    capabilities: u16,
+    svc_capabilities: u32,
+    mvc_capabilities: u32,
+    min_bit_rate: u32,
+    max_bit_rate: u32,
+    default_frame_interval: Duration,
+    frame_interval: SupportedFrameIntervals,
+}
+
+impl FrameH264 {
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the sample aspect ratio as `(width, height)`.
+    pub fn sample_aspect_ratio(&self) -> (u16, u16) {
+        (self.sar_width, self.sar_height)
+    }
+
+    /// Returns the raw `wProfile` value (`profile_idc` in the high byte, the constraint flags in
+    /// the low byte).
+    pub fn profile(&self) -> u16 {
+        self.profile
+    }
+
+    /// Returns the H.264 `level_idc` (eg. 31 for level 3.1).
+    pub fn level_idc(&self) -> u8 {
+        self.level_idc
+    }
+
+    /// Returns the raw `wConstrainedToolset` value (reserved, should be 0).
+    pub fn constrained_toolset(&self) -> u16 {
+        self.constrained_toolset
+    }
+
+    /// Returns the raw `bmSupportedUsages` bitmap.
+    pub fn supported_usages(&self) -> u32 {
+        self.supported_usages
+    }
+
+    /// Returns the raw `bmCapabilities` bitmap.
+    pub fn capabilities(&self) -> u16 {
+        self.capabilities
+    }
+
+    /// Returns the raw `bmSVCCapabilities` bitmap.
+    pub fn svc_capabilities(&self) -> u32 {
+        self.svc_capabilities
+    }
+
+    /// Returns the raw `bmMVCCapabilities` bitmap.
+    pub fn mvc_capabilities(&self) -> u32 {
+        self.mvc_capabilities
+    }
+
+    /// Returns the minimum bit rate in bits per second.
+    pub fn min_bit_rate(&self) -> u32 {
+        self.min_bit_rate
+    }
+
+    /// Returns the maximum bit rate in bits per second.
+    pub fn max_bit_rate(&self) -> u32 {
+        self.max_bit_rate
+    }
+
+    pub fn default_frame_interval(&self) -> Duration {
+        self.default_frame_interval
+    }
+
+    pub fn frame_intervals(&self) -> &SupportedFrameIntervals {
+        &self.frame_interval
+    }
+}
+
+/// VP8 frame (UVC 1.5).
+#[derive(Debug)]
+pub struct FrameVp8 {
+    width: u16,
+    height: u16,
+    supported_usages: u32,
+    capabilities: u16,
+    scalability_capabilities: u32,
+    min_bit_rate: u32,
+    max_bit_rate: u32,
+    default_frame_interval: Duration,
+    frame_interval: SupportedFrameIntervals,
+}
+
+impl FrameVp8 {
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the raw `bmSupportedUsages` bitmap.
+    pub fn supported_usages(&self) -> u32 {
+        self.supported_usages
+    }
+
+    /// Returns the raw `bmCapabilities` bitmap.
+    pub fn capabilities(&self) -> u16 {
+        self.capabilities
+    }
+
+    /// Returns the raw `bmScalabilityCapabilities` bitmap, which includes the number of supported
+    /// temporal layers.
+    pub fn scalability_capabilities(&self) -> u32 {
+        self.scalability_capabilities
+    }
+
+    /// Returns the minimum bit rate in bits per second.
+    pub fn min_bit_rate(&self) -> u32 {
+        self.min_bit_rate
+    }
+
+    /// Returns the maximum bit rate in bits per second.
+    pub fn max_bit_rate(&self) -> u32 {
+        self.max_bit_rate
+    }
+
+    pub fn default_frame_interval(&self) -> Duration {
+        self.default_frame_interval
+    }
+
+    pub fn frame_intervals(&self) -> &SupportedFrameIntervals {
+        &self.frame_interval
+    }
+}
+
 #[derive(Debug)]
 pub enum SupportedFrameIntervals {
     Continuous {
@@ -1,11 +1,11 @@
-use std::io;
+use std::{cell::RefCell, io};
 
 use byteorder::{ReadBytesExt, LE};
-use rusb::InterfaceDescriptor;
+use rusb::{Interface, InterfaceDescriptor};
 
 use crate::{
     error::*,
-    util::{io_err, io_err_res, split_descriptors, BcdVersion, BytesExt},
+    util::{io_err, io_err_res, split_descriptors, truncate_bitmask, BcdVersion, BytesExt},
     Result,
 };
 
@@ -42,32 +42,80 @@ const STREAM_DESC_SUBTYPE_FORMAT_VP8: u8 = 0x16;
 const STREAM_DESC_SUBTYPE_FRAME_VP8: u8 = 0x17;
 const STREAM_DESC_SUBTYPE_FORMAT_VP8_SIMULCAST: u8 = 0x18;
 
-pub(crate) fn parse_control_desc(desc: &InterfaceDescriptor<'_>) -> Result<Topology> {
+/// Default number of zero Bytes appended to descriptors that are too short before parsing them
+/// again.
+pub(crate) const DEFAULT_REPAIR_PADDING: usize = 100;
+
+/// Collects [`ParseWarning`]s while parsing descriptors.
+#[derive(Default)]
+pub(crate) struct ParseWarnings(RefCell<Vec<ParseWarning>>);
+
+impl ParseWarnings {
+    /// Logs `message` as a warning and records it.
+    pub(crate) fn warn(&self, message: impl Into<String>) {
+        let message = message.into();
+        log::warn!("{}", message);
+        self.0.borrow_mut().push(ParseWarning { message });
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<ParseWarning> {
+        self.0.into_inner()
+    }
+}
+
+fn locate(ty: u8, data: &[u8], offset: usize) -> DescriptorLocation {
+    DescriptorLocation {
+        ty,
+        subtype: data.get(2).copied(),
+        offset,
+    }
+}
+
+pub(crate) fn parse_control_desc(
+    desc: &InterfaceDescriptor<'_>,
+    repair_padding: usize,
+    warnings: &ParseWarnings,
+) -> Result<Topology> {
+    parse_control_extra(desc.extra(), repair_padding, warnings)
+}
+
+/// Parses the class-specific descriptors of a Video Control interface.
+///
+/// `extra` is the raw descriptor data following the standard interface descriptor. Descriptors
+/// that are too short are padded with `repair_padding` zero Bytes and parsed again (or rejected if
+/// it is 0).
+pub(crate) fn parse_control_extra(
+    extra: &[u8],
+    repair_padding: usize,
+    warnings: &ParseWarnings,
+) -> Result<Topology> {
     let mut parser = ControlDescParser {
+        repair_padding,
         header: None,
         units: Vec::new(),
         inputs: Vec::new(),
         outputs: Vec::new(),
+        unknown_descriptors: Vec::new(),
     };
-    for (ty, data) in split_descriptors(desc.extra()) {
+    let mut offset = 0;
+    for (ty, data) in split_descriptors(extra, warnings) {
         if ty == VIDEO_INTERFACE_DESC_TYPE {
             parser
-                .parse_descriptor(&data[2..])
-                .during(Action::AccessingDeviceDescriptor)?;
+                .parse_descriptor(&data[2..], warnings)
+                .during(Action::ParsingDescriptor(Some(locate(ty, data, offset))))?;
         } else {
             log::debug!("skipping descriptor of type {}", ty);
+            parser.unknown_descriptors.push((ty, data.to_vec()));
         }
+        offset += data.len();
     }
 
-    // FIXME: the interrupt endpoint descriptor also carries custom data according to the spec,
-    // however it is absent on the Leap Motion (or `lsusb` doesn't display it)
-
     let header = match parser.header {
         Some(header) => header,
         None => {
             return err(
                 "missing VC_HEADER descriptor",
-                Action::AccessingDeviceDescriptor,
+                Action::ParsingDescriptor(None),
             );
         }
     };
@@ -77,36 +125,46 @@ pub(crate) fn parse_control_desc(desc: &InterfaceDescriptor<'_>) -> Result<Topol
         units: parser.units,
         inputs: parser.inputs,
         outputs: parser.outputs,
+        unknown_descriptors: parser.unknown_descriptors,
     })
 }
 
 struct ControlDescParser {
+    repair_padding: usize,
     header: Option<ControlHeader>,
     units: Vec<UnitDesc>,
     inputs: Vec<InputTerminalDesc>,
     outputs: Vec<OutputTerminalDesc>,
+    unknown_descriptors: Vec<(u8, Vec<u8>)>,
 }
 
 impl ControlDescParser {
-    fn parse_descriptor(&mut self, raw: &[u8]) -> io::Result<()> {
-        match self.parse_descriptor_impl(raw) {
+    fn parse_descriptor(&mut self, raw: &[u8], warnings: &ParseWarnings) -> io::Result<()> {
+        match self.parse_descriptor_impl(raw, warnings) {
             Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                log::warn!(
-                    "UVC descriptor too short, please report a bug to the device manufacturer"
+                warnings.warn(
+                    "UVC descriptor too short, please report a bug to the device manufacturer",
                 );
-                log::debug!("retrying with 100 extra zero bytes");
+                if self.repair_padding == 0 {
+                    return Err(e);
+                }
+                log::debug!("retrying with {} extra zero bytes", self.repair_padding);
                 log::debug!("descriptor data: {:02x?}", raw);
 
-                let mut buf = vec![0; raw.len() + 100];
+                let mut buf = vec![0; raw.len() + self.repair_padding];
                 buf[..raw.len()].copy_from_slice(raw);
 
-                self.parse_descriptor_impl(&buf)
+                self.parse_descriptor_impl(&buf, warnings)
             }
             res => res,
         }
     }
 
-    fn parse_descriptor_impl(&mut self, mut raw: &[u8]) -> io::Result<()> {
+    fn parse_descriptor_impl(
+        &mut self,
+        mut raw: &[u8],
+        warnings: &ParseWarnings,
+    ) -> io::Result<()> {
         let subtype = raw.read_u8()?;
         match subtype {
             CONTROL_DESC_SUBTYPE_HEADER => {
@@ -139,13 +197,18 @@ impl ControlDescParser {
                     kind: InputTerminalKind::Other,
                 };
                 if term.terminal_type() == Some(InputTerminalType::InCamera) {
+                    let objective_focal_length_min = raw.read_u16::<LE>()?;
+                    let objective_focal_length_max = raw.read_u16::<LE>()?;
+                    let ocular_focal_length = raw.read_u16::<LE>()?;
+                    let raw_controls = raw.read_length_prefixed_bitmask_vec()?;
                     term.kind = InputTerminalKind::Camera(CameraTerminalDesc {
-                        objective_focal_length_min: raw.read_u16::<LE>()?,
-                        objective_focal_length_max: raw.read_u16::<LE>()?,
-                        ocular_focal_length: raw.read_u16::<LE>()?,
-                        controls: CameraControls::from_bits_truncate(
-                            raw.read_length_prefixed_bitmask()?,
-                        ),
+                        objective_focal_length_min,
+                        objective_focal_length_max,
+                        ocular_focal_length,
+                        controls: CameraControls::from_bits_truncate(truncate_bitmask(
+                            &raw_controls,
+                        )),
+                        raw_controls,
                     });
                 }
 
@@ -185,14 +248,21 @@ impl ControlDescParser {
                 // the length byte (28 -> 0x1c) of the next descriptor as the `standards` field.
                 // In our case, this is handled by the `parse_descriptor` fallback.
 
+                let id = ProcessingUnitId(raw.read_nonzero_unit_id()?);
+                let source = raw.read_nonzero_source_id()?;
+                let max_multiplier = raw.read_u16::<LE>()?;
+                // Keep the whole bitmap around, since later UVC versions may define more controls
+                // than `ProcessingUnitControls` can hold.
+                let raw_controls = raw.read_length_prefixed_bitmask_vec()?;
                 self.units.push(UnitDesc {
                     kind: UnitKind::Processing(ProcessingUnitDesc {
-                        id: ProcessingUnitId(raw.read_nonzero_unit_id()?),
-                        source: raw.read_nonzero_source_id()?,
-                        max_multiplier: raw.read_u16::<LE>()?,
-                        controls: ProcessingUnitControls::from_bits_truncate(
-                            raw.read_length_prefixed_bitmask()?,
-                        ),
+                        id,
+                        source,
+                        max_multiplier,
+                        controls: ProcessingUnitControls::from_bits_truncate(truncate_bitmask(
+                            &raw_controls,
+                        )),
+                        raw_controls,
                         string: raw.read_u8()?,
                         standards: VideoStandards::from_bits_truncate(raw.read_u8()?),
                     }),
@@ -206,16 +276,28 @@ impl ControlDescParser {
                         extension_code: raw.read_guid()?,
                         num_controls: raw.read_u8()?,
                         inputs: {
+                            // Leave room for `bControlSize` and `iExtension`.
                             let count = raw.read_u8()?;
+                            let count = clamp_count(
+                                count,
+                                raw.len().saturating_sub(2),
+                                "bNrInPins",
+                                warnings,
+                            );
                             (0..count)
                                 .map(|_| raw.read_nonzero_source_id())
                                 .collect::<io::Result<Vec<_>>>()?
                         },
                         controls_bitmap: {
+                            // Leave room for `iExtension`.
                             let size = raw.read_u8()?;
-                            (0..size)
-                                .map(|_| raw.read_u8())
-                                .collect::<io::Result<Vec<_>>>()?
+                            let size = clamp_count(
+                                size,
+                                raw.len().saturating_sub(1),
+                                "bControlSize",
+                                warnings,
+                            );
+                            raw.read_bitmask_vec(size)?
                         },
                     }),
                 });
@@ -230,76 +312,204 @@ impl ControlDescParser {
     }
 }
 
+/// Clamps a count of 1-Byte elements declared in a descriptor to the number of Bytes available.
+///
+/// This prevents a corrupt count from making the parser read past the end of the descriptor.
+fn clamp_count(count: u8, available: usize, field: &str, warnings: &ParseWarnings) -> u8 {
+    if usize::from(count) > available {
+        warnings.warn(format!(
+            "`{}` is {}, but only {} bytes are left in the descriptor; clamping",
+            field, count, available
+        ));
+        available as u8
+    } else {
+        count
+    }
+}
+
 pub(crate) fn parse_streaming_descriptor(
+    interface: &Interface<'_>,
     desc: &InterfaceDescriptor<'_>,
+    repair_padding: usize,
+    warnings: &ParseWarnings,
+) -> Result<StreamingInterfaceDesc> {
+    let mut desc = parse_streaming_extra(
+        desc.interface_number(),
+        desc.extra(),
+        repair_padding,
+        warnings,
+    )?;
+    desc.alt_settings = parse_alt_settings(interface, desc.still_endpoint_address());
+    Ok(desc)
+}
+
+/// Parses the class-specific descriptors of the Video Streaming interface `interface_number`.
+///
+/// `extra` is the raw descriptor data following the standard interface descriptor. Since the
+/// alternate settings are not part of that data, the returned descriptor does not list any.
+/// `repair_padding` works like in [`parse_control_extra`].
+pub(crate) fn parse_streaming_extra(
+    interface_number: u8,
+    extra: &[u8],
+    repair_padding: usize,
+    warnings: &ParseWarnings,
 ) -> Result<StreamingInterfaceDesc> {
     let mut parser = StreamingDescParser {
+        repair_padding,
         in_header: None,
         out_header: None,
         formats: Vec::new(),
         frames: Vec::new(),
+        still_image_frames: Vec::new(),
+        unknown_descriptors: Vec::new(),
     };
 
-    for (ty, data) in split_descriptors(desc.extra()) {
+    // Many format types are not supported yet, so a descriptor that fails to parse only causes
+    // an error if it leaves us without any usable format.
+    let mut first_error = None;
+    let mut offset = 0;
+    for (ty, data) in split_descriptors(extra, warnings) {
         if ty == VIDEO_INTERFACE_DESC_TYPE {
-            parser
-                .parse_descriptor(&data[2..])
-                .during(Action::AccessingDeviceDescriptor)?;
+            let res = parser
+                .parse_descriptor(&data[2..], warnings)
+                .during(Action::ParsingDescriptor(Some(locate(ty, data, offset))));
+            if let Err(e) = res {
+                warnings.warn(format!(
+                    "skipping Video Streaming descriptor of interface {}: {}",
+                    interface_number, e
+                ));
+                log::debug!("descriptor data: {:02x?}", data);
+                first_error.get_or_insert(e);
+            }
         } else {
             log::debug!("skipping descriptor of type {}", ty);
+            parser.unknown_descriptors.push((ty, data.to_vec()));
+        }
+        offset += data.len();
+    }
+
+    // Stream-based formats (eg. MPEG-2 TS) have no frame descriptors.
+    let needs_frames = parser.formats.iter().any(|f| f.num_frame_descriptors != 0);
+    if parser.formats.is_empty() || (needs_frames && parser.frames.is_empty()) {
+        if let Some(e) = first_error {
+            return Err(e);
         }
     }
 
-    Ok(StreamingInterfaceDesc {
-        id: StreamingInterfaceId(desc.interface_number()),
+    let desc = StreamingInterfaceDesc {
+        id: StreamingInterfaceId(interface_number),
         kind: match (parser.in_header, parser.out_header) {
             (None, Some(output)) => StreamingInterfaceKind::Output(output),
             (Some(input), None) => StreamingInterfaceKind::Input(input),
             (None, None) => {
                 return err(
                     "missing header in Video Streaming interface",
-                    Action::AccessingDeviceDescriptor,
+                    Action::ParsingDescriptor(None),
                 )
             }
             (Some(_), Some(_)) => {
                 return err(
                     "Video Streaming interface has both input and output descriptor",
-                    Action::AccessingDeviceDescriptor,
+                    Action::ParsingDescriptor(None),
                 )
             }
         },
         formats: parser.formats,
         frames: parser.frames,
-    })
+        still_image_frames: parser.still_image_frames,
+        unknown_descriptors: parser.unknown_descriptors,
+        alt_settings: Vec::new(),
+    };
+
+    // A mismatched endpoint direction would make reads or writes on the endpoint fail or hang.
+    let expected = match desc.kind {
+        StreamingInterfaceKind::Input(_) => EndpointDirection::In,
+        StreamingInterfaceKind::Output(_) => EndpointDirection::Out,
+    };
+    if desc.endpoint_direction() != expected {
+        return err(
+            format!(
+                "Video Streaming interface {} uses endpoint {:#04x}, which has the wrong direction (expected {:?})",
+                desc.id.0,
+                desc.endpoint_address(),
+                expected
+            ),
+            Action::ParsingDescriptor(None),
+        );
+    }
+
+    Ok(desc)
+}
+
+/// Parses the alternate settings of a Video Streaming interface.
+///
+/// `still_endpoint` is the address of the interface's still image endpoint (capture method 3),
+/// which is told apart from the video endpoint by its address.
+fn parse_alt_settings(interface: &Interface<'_>, still_endpoint: Option<u8>) -> Vec<AltSetting> {
+    interface
+        .descriptors()
+        .map(|desc| {
+            let is_still = |address| Some(address) == still_endpoint;
+            let ep = desc
+                .endpoint_descriptors()
+                .find(|ep| !is_still(ep.address()));
+            AltSetting {
+                number: desc.setting_number(),
+                endpoint_address: ep.as_ref().map(|ep| ep.address()),
+                max_packet_size: ep.as_ref().map_or(0, |ep| ep.max_packet_size()),
+                interval: ep.map_or(0, |ep| ep.interval()),
+                has_still_endpoint: desc.endpoint_descriptors().any(|ep| is_still(ep.address())),
+            }
+        })
+        .collect()
 }
 
 struct StreamingDescParser {
+    repair_padding: usize,
     in_header: Option<InputHeader>,
     out_header: Option<OutputHeader>,
     formats: Vec<Format>,
     frames: Vec<Frame>,
+    still_image_frames: Vec<StillImageFrame>,
+    unknown_descriptors: Vec<(u8, Vec<u8>)>,
 }
 
 impl StreamingDescParser {
-    fn parse_descriptor(&mut self, raw: &[u8]) -> io::Result<()> {
-        match self.parse_descriptor_impl(raw) {
+    /// Returns the index of the last format descriptor, which the following frame descriptors
+    /// belong to.
+    fn current_format_index(&self) -> io::Result<FormatIndex> {
+        match self.formats.last() {
+            Some(format) => Ok(format.index()),
+            None => io_err_res("frame descriptor without preceding format descriptor"),
+        }
+    }
+
+    fn parse_descriptor(&mut self, raw: &[u8], warnings: &ParseWarnings) -> io::Result<()> {
+        match self.parse_descriptor_impl(raw, warnings) {
             Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                log::warn!(
-                    "UVC Video Streaming interface descriptor too short, please report a bug to the device manufacturer"
+                warnings.warn(
+                    "UVC Video Streaming interface descriptor too short, please report a bug to the device manufacturer",
                 );
-                log::debug!("retrying with 100 extra zero bytes");
+                if self.repair_padding == 0 {
+                    return Err(e);
+                }
+                log::debug!("retrying with {} extra zero bytes", self.repair_padding);
                 log::debug!("descriptor data: {:#04x?}", raw);
 
-                let mut buf = vec![0; raw.len() + 100];
+                let mut buf = vec![0; raw.len() + self.repair_padding];
                 buf[..raw.len()].copy_from_slice(raw);
 
-                self.parse_descriptor_impl(&buf)
+                self.parse_descriptor_impl(&buf, warnings)
             }
             res => res,
         }
     }
 
-    fn parse_descriptor_impl(&mut self, mut raw: &[u8]) -> io::Result<()> {
+    fn parse_descriptor_impl(
+        &mut self,
+        mut raw: &[u8],
+        warnings: &ParseWarnings,
+    ) -> io::Result<()> {
         let subtype = raw.read_u8()?;
         match subtype {
             STREAM_DESC_SUBTYPE_INPUT_HEADER => {
@@ -317,21 +527,22 @@ impl StreamingDescParser {
                     still_capture_method: {
                         let raw = raw.read_u8()?;
                         StillCaptureMethod::from_raw(raw).unwrap_or_else(|| {
-                            log::warn!("invalid value {} for `bStillCaptureMethod`", raw);
+                            warnings
+                                .warn(format!("invalid value {} for `bStillCaptureMethod`", raw));
                             StillCaptureMethod::None
                         })
                     },
                     trigger_support: {
                         let raw = raw.read_u8()?;
                         TriggerSupport::from_raw(raw).unwrap_or_else(|| {
-                            log::warn!("invalid value {} for `bTriggerSupport`", raw);
+                            warnings.warn(format!("invalid value {} for `bTriggerSupport`", raw));
                             TriggerSupport::NotSupported
                         })
                     },
                     trigger_usage: {
                         let raw = raw.read_u8()?;
                         TriggerUsage::from_raw(raw).unwrap_or_else(|| {
-                            log::warn!("invalid value {} for `bTriggerUsage`", raw);
+                            warnings.warn(format!("invalid value {} for `bTriggerUsage`", raw));
                             TriggerUsage::InitiateStillImageCapture
                         })
                     },
@@ -349,10 +560,34 @@ impl StreamingDescParser {
                 });
                 Ok(())
             }
+            STREAM_DESC_SUBTYPE_OUTPUT_HEADER => {
+                if self.out_header.is_some() {
+                    return io_err_res("duplicate output header descriptor");
+                }
+
+                let num_formats = raw.read_u8()?;
+                let _total_length = raw.read_u16::<LE>()?;
+                self.out_header = Some(OutputHeader {
+                    endpoint_address: raw.read_u8()?,
+                    terminal_link: raw.read_nonzero_term_id()?,
+                    format_controls: {
+                        let control_size = raw.read_u8()?;
+
+                        (0..num_formats)
+                            .map(|_| {
+                                raw.read_bitmask(control_size)
+                                    .map(PerFormatControls::from_bits_truncate)
+                            })
+                            .collect::<io::Result<Vec<_>>>()?
+                    },
+                });
+                Ok(())
+            }
             STREAM_DESC_SUBTYPE_FORMAT_UNCOMPRESSED => {
                 self.formats.push(Format {
                     format_index: FormatIndex(raw.read_u8()?),
                     num_frame_descriptors: raw.read_u8()?,
+                    color_matching: None,
                     kind: FormatKind::Uncompressed(FormatUncompressed {
                         format: raw.read_guid()?,
                         bits_per_pixel: raw.read_u8()?,
@@ -367,6 +602,7 @@ impl StreamingDescParser {
             }
             STREAM_DESC_SUBTYPE_FRAME_UNCOMPRESSED => {
                 self.frames.push(Frame {
+                    format_index: self.current_format_index()?,
                     frame_index: FrameIndex(raw.read_u8()?),
                     kind: FrameKind::Uncompressed(FrameUncompressed {
                         capabilities: UncompressedFrameCapabilities::from_bits_truncate(
@@ -378,46 +614,223 @@ impl StreamingDescParser {
                         max_bit_rate: raw.read_u32::<LE>()?,
                         max_video_frame_buffer_size: raw.read_u32::<LE>()?,
                         default_frame_interval: raw.read_time_100ns()?,
-                        frame_interval: {
-                            let ty = raw.read_u8()?;
-                            match ty {
-                                0 => {
-                                    // Continuous
-                                    SupportedFrameIntervals::Continuous {
-                                        min_frame_interval: raw.read_time_100ns()?,
-                                        max_frame_interval: raw.read_time_100ns()?,
-                                        frame_interval_step: raw.read_time_100ns()?,
-                                    }
-                                }
-                                n => {
-                                    // `n` discrete intervals.
-                                    SupportedFrameIntervals::Discrete {
-                                        supported_frame_intervals: (0..n)
-                                            .map(|_| raw.read_time_100ns())
-                                            .collect::<io::Result<Vec<_>>>()?,
-                                    }
-                                }
-                            }
+                        frame_interval: read_frame_intervals(&mut raw)?,
+                    }),
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_FORMAT_MJPEG => {
+                self.formats.push(Format {
+                    format_index: FormatIndex(raw.read_u8()?),
+                    num_frame_descriptors: raw.read_u8()?,
+                    color_matching: None,
+                    kind: FormatKind::Mjpeg(FormatMjpeg {
+                        flags: MjpegFormatFlags::from_bits_truncate(raw.read_u8()?),
+                        default_frame_index: FrameIndex(raw.read_u8()?),
+                        aspect_ratio_x: raw.read_u8()?,
+                        aspect_ratio_y: raw.read_u8()?,
+                        interlace_flags: InterlaceFlags::from_bits_truncate(raw.read_u8()?),
+                        copy_protect: raw.read_u8()?,
+                    }),
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_FRAME_MJPEG => {
+                self.frames.push(Frame {
+                    format_index: self.current_format_index()?,
+                    frame_index: FrameIndex(raw.read_u8()?),
+                    kind: FrameKind::Mjpeg(FrameMjpeg {
+                        capabilities: MjpegFrameCapabilities::from_bits_truncate(raw.read_u8()?),
+                        width: raw.read_u16::<LE>()?,
+                        height: raw.read_u16::<LE>()?,
+                        min_bit_rate: raw.read_u32::<LE>()?,
+                        max_bit_rate: raw.read_u32::<LE>()?,
+                        max_video_frame_buffer_size: raw.read_u32::<LE>()?,
+                        default_frame_interval: raw.read_time_100ns()?,
+                        frame_interval: read_frame_intervals(&mut raw)?,
+                    }),
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_FORMAT_H264 => {
+                self.formats.push(Format {
+                    format_index: FormatIndex(raw.read_u8()?),
+                    num_frame_descriptors: raw.read_u8()?,
+                    color_matching: None,
+                    kind: FormatKind::H264(FormatH264 {
+                        default_frame_index: FrameIndex(raw.read_u8()?),
+                        max_codec_config_delay: raw.read_u8()?,
+                        supported_slice_modes: raw.read_u8()?,
+                        supported_sync_frame_types: raw.read_u8()?,
+                        resolution_scaling: raw.read_u8()?,
+                        supported_rate_control_modes: {
+                            let _reserved = raw.read_u8()?;
+                            raw.read_u8()?
+                        },
+                        // The maximum macroblock processing rates that follow are not exposed.
+                    }),
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_FRAME_H264 => {
+                self.frames.push(Frame {
+                    format_index: self.current_format_index()?,
+                    frame_index: FrameIndex(raw.read_u8()?),
+                    kind: FrameKind::H264(FrameH264 {
+                        width: raw.read_u16::<LE>()?,
+                        height: raw.read_u16::<LE>()?,
+                        sar_width: raw.read_u16::<LE>()?,
+                        sar_height: raw.read_u16::<LE>()?,
+                        profile: raw.read_u16::<LE>()?,
+                        level_idc: raw.read_u8()?,
+                        constrained_toolset: raw.read_u16::<LE>()?,
+                        supported_usages: raw.read_u32::<LE>()?,
+                        capabilities: raw.read_u16::<LE>()?,
+                        svc_capabilities: raw.read_u32::<LE>()?,
+                        mvc_capabilities: raw.read_u32::<LE>()?,
+                        min_bit_rate: raw.read_u32::<LE>()?,
+                        max_bit_rate: raw.read_u32::<LE>()?,
+                        default_frame_interval: raw.read_time_100ns()?,
+                        // H.264 frames only support discrete frame intervals, but use the same
+                        // encoding.
+                        frame_interval: read_frame_intervals(&mut raw)?,
+                    }),
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_FORMAT_VP8 => {
+                self.formats.push(Format {
+                    format_index: FormatIndex(raw.read_u8()?),
+                    num_frame_descriptors: raw.read_u8()?,
+                    color_matching: None,
+                    kind: FormatKind::Vp8(FormatVp8 {
+                        default_frame_index: FrameIndex(raw.read_u8()?),
+                        max_codec_config_delay: raw.read_u8()?,
+                        supported_partition_count: raw.read_u8()?,
+                        supported_sync_frame_types: raw.read_u8()?,
+                        resolution_scaling: raw.read_u8()?,
+                        supported_rate_control_modes: raw.read_u8()?,
+                        max_mb_per_sec: raw.read_u16::<LE>()?,
+                    }),
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_FRAME_VP8 => {
+                self.frames.push(Frame {
+                    format_index: self.current_format_index()?,
+                    frame_index: FrameIndex(raw.read_u8()?),
+                    kind: FrameKind::Vp8(FrameVp8 {
+                        width: raw.read_u16::<LE>()?,
+                        height: raw.read_u16::<LE>()?,
+                        supported_usages: raw.read_u32::<LE>()?,
+                        capabilities: raw.read_u16::<LE>()?,
+                        scalability_capabilities: raw.read_u32::<LE>()?,
+                        min_bit_rate: raw.read_u32::<LE>()?,
+                        max_bit_rate: raw.read_u32::<LE>()?,
+                        default_frame_interval: raw.read_time_100ns()?,
+                        // Like H.264, VP8 frames only support discrete frame intervals.
+                        frame_interval: read_frame_intervals(&mut raw)?,
+                    }),
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_FORMAT_MPEG2TS => {
+                self.formats.push(Format {
+                    format_index: FormatIndex(raw.read_u8()?),
+                    num_frame_descriptors: 0,
+                    color_matching: None,
+                    kind: FormatKind::Mpeg2Ts(FormatMpeg2Ts {
+                        data_offset: raw.read_u8()?,
+                        packet_length: raw.read_u8()?,
+                        stride_length: raw.read_u8()?,
+                        // `guidStrideFormat` was added in UVC 1.1.
+                        stride_format: if raw.is_empty() {
+                            None
+                        } else {
+                            Some(raw.read_guid()?)
                         },
                     }),
                 });
                 Ok(())
             }
-            STREAM_DESC_SUBTYPE_OUTPUT_HEADER
-            | STREAM_DESC_SUBTYPE_STILL_IMAGE_FRAME
-            | STREAM_DESC_SUBTYPE_FORMAT_MJPEG
-            | STREAM_DESC_SUBTYPE_FRAME_MJPEG
-            | STREAM_DESC_SUBTYPE_FORMAT_MPEG2TS
-            | STREAM_DESC_SUBTYPE_FORMAT_DV
-            | STREAM_DESC_SUBTYPE_COLORFORMAT
-            | STREAM_DESC_SUBTYPE_FORMAT_FRAME_BASED
+            STREAM_DESC_SUBTYPE_FORMAT_DV => {
+                self.formats.push(Format {
+                    format_index: FormatIndex(raw.read_u8()?),
+                    num_frame_descriptors: 0,
+                    color_matching: None,
+                    kind: FormatKind::Dv(FormatDv {
+                        max_video_frame_buffer_size: raw.read_u32::<LE>()?,
+                        format_type: raw.read_u8()?,
+                    }),
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_STILL_IMAGE_FRAME => {
+                let format_index = self.current_format_index()?;
+                let endpoint_address = raw.read_u8()?;
+                let num_sizes = raw.read_u8()?;
+                let image_sizes = (0..num_sizes)
+                    .map(|_| Ok((raw.read_u16::<LE>()?, raw.read_u16::<LE>()?)))
+                    .collect::<io::Result<Vec<_>>>()?;
+                let num_compressions = raw.read_u8()?;
+                let compressions = (0..num_compressions)
+                    .map(|_| raw.read_u8())
+                    .collect::<io::Result<Vec<_>>>()?;
+                self.still_image_frames.push(StillImageFrame {
+                    format_index,
+                    // Method 2 uses the video endpoint, and sets this to 0.
+                    endpoint_address: if endpoint_address == 0 {
+                        None
+                    } else {
+                        Some(endpoint_address)
+                    },
+                    image_sizes,
+                    compressions,
+                });
+                Ok(())
+            }
+            STREAM_DESC_SUBTYPE_COLORFORMAT => {
+                let color_matching = ColorMatching {
+                    color_primaries: {
+                        let raw = raw.read_u8()?;
+                        ColorPrimaries::from_raw(raw).unwrap_or_else(|| {
+                            warnings.warn(format!("invalid value {} for `bColorPrimaries`", raw));
+                            ColorPrimaries::Bt709
+                        })
+                    },
+                    transfer_characteristics: {
+                        let raw = raw.read_u8()?;
+                        TransferCharacteristics::from_raw(raw).unwrap_or_else(|| {
+                            warnings.warn(format!(
+                                "invalid value {} for `bTransferCharacteristics`",
+                                raw
+                            ));
+                            TransferCharacteristics::Bt709
+                        })
+                    },
+                    matrix_coefficients: {
+                        let raw = raw.read_u8()?;
+                        MatrixCoefficients::from_raw(raw).unwrap_or_else(|| {
+                            warnings
+                                .warn(format!("invalid value {} for `bMatrixCoefficients`", raw));
+                            MatrixCoefficients::Bt709
+                        })
+                    },
+                };
+                match self.formats.last_mut() {
+                    Some(format) => {
+                        format.color_matching = Some(color_matching);
+                        Ok(())
+                    }
+                    None => {
+                        io_err_res("color matching descriptor without preceding format descriptor")
+                    }
+                }
+            }
+            STREAM_DESC_SUBTYPE_FORMAT_FRAME_BASED
             | STREAM_DESC_SUBTYPE_FRAME_FRAME_BASED
             | STREAM_DESC_SUBTYPE_FORMAT_STREAM_BASED
-            | STREAM_DESC_SUBTYPE_FORMAT_H264
-            | STREAM_DESC_SUBTYPE_FRAME_H264
             | STREAM_DESC_SUBTYPE_FORMAT_H264_SIMULCAST
-            | STREAM_DESC_SUBTYPE_FORMAT_VP8
-            | STREAM_DESC_SUBTYPE_FRAME_VP8
             | STREAM_DESC_SUBTYPE_FORMAT_VP8_SIMULCAST => {
                 // TODO
                 io_err_res(format!("unimplemented descriptor subtype {}", subtype))
@@ -426,3 +839,337 @@ impl StreamingDescParser {
         }
     }
 }
+
+/// Reads `bFrameIntervalType` and the frame interval fields that follow it.
+fn read_frame_intervals(raw: &mut &[u8]) -> io::Result<SupportedFrameIntervals> {
+    let ty = raw.read_u8()?;
+    Ok(match ty {
+        0 => {
+            // Continuous
+            SupportedFrameIntervals::Continuous {
+                min_frame_interval: raw.read_time_100ns()?,
+                max_frame_interval: raw.read_time_100ns()?,
+                frame_interval_step: raw.read_time_100ns()?,
+            }
+        }
+        n => {
+            // `n` discrete intervals.
+            SupportedFrameIntervals::Discrete {
+                supported_frame_intervals: (0..n)
+                    .map(|_| raw.read_time_100ns())
+                    .collect::<io::Result<Vec<_>>>()?,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Class-specific Video Control descriptors of a webcam, modeled after a Logitech C920 (with
+    /// only one of its extension units).
+    #[rustfmt::skip]
+    const C920_CONTROL: &[u8] = &[
+        // VC_HEADER
+        0x0d, 0x24, 0x01, 0x00, 0x01, 0x4f, 0x00, 0x80, 0xc3, 0xc9, 0x01, 0x01, 0x01,
+        // VC_INPUT_TERMINAL (camera)
+        0x12, 0x24, 0x02, 0x01, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+        0x2e, 0x0a, 0x02,
+        // VC_PROCESSING_UNIT
+        0x0c, 0x24, 0x05, 0x03, 0x01, 0x00, 0x40, 0x02, 0x5b, 0x17, 0x00, 0x1b,
+        // VC_EXTENSION_UNIT
+        0x1b, 0x24, 0x06, 0x06, 0xd0, 0x9e, 0xe4, 0x23, 0x78, 0x11, 0x31, 0x4f, 0xae, 0x52, 0xd2,
+        0xfb, 0x8a, 0x8d, 0x3b, 0x48, 0x0a, 0x01, 0x03, 0x02, 0xff, 0x03, 0x00,
+        // VC_OUTPUT_TERMINAL
+        0x09, 0x24, 0x03, 0x04, 0x01, 0x01, 0x00, 0x06, 0x00,
+    ];
+
+    /// Class-specific descriptors of the matching Video Streaming interface (without the H.264
+    /// format).
+    #[rustfmt::skip]
+    const C920_STREAMING: &[u8] = &[
+        // VS_INPUT_HEADER
+        0x0f, 0x24, 0x01, 0x02, 0xa3, 0x00, 0x81, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // VS_FORMAT_UNCOMPRESSED (YUY2)
+        0x1b, 0x24, 0x04, 0x01, 0x01, 0x59, 0x55, 0x59, 0x32, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00,
+        0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // VS_FRAME_UNCOMPRESSED (640x480)
+        0x22, 0x24, 0x05, 0x01, 0x00, 0x80, 0x02, 0xe0, 0x01, 0x00, 0x00, 0x77, 0x01, 0x00, 0x00,
+        0xca, 0x08, 0x00, 0x60, 0x09, 0x00, 0x15, 0x16, 0x05, 0x00, 0x02, 0x15, 0x16, 0x05, 0x00,
+        0x2a, 0x2c, 0x0a, 0x00,
+        // VS_COLORFORMAT
+        0x06, 0x24, 0x0d, 0x01, 0x01, 0x04,
+        // VS_FORMAT_MJPEG
+        0x0b, 0x24, 0x06, 0x02, 0x02, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00,
+        // VS_FRAME_MJPEG (1920x1080)
+        0x1e, 0x24, 0x07, 0x01, 0x00, 0x80, 0x07, 0x38, 0x04, 0x00, 0xc0, 0xa9, 0x1d, 0x00, 0x80,
+        0x53, 0x3b, 0x4d, 0x4a, 0x3f, 0x00, 0x15, 0x16, 0x05, 0x00, 0x01, 0x15, 0x16, 0x05, 0x00,
+        // VS_FRAME_MJPEG (1280x720)
+        0x22, 0x24, 0x07, 0x02, 0x00, 0x00, 0x05, 0xd0, 0x02, 0x00, 0x00, 0x2f, 0x0d, 0x00, 0x00,
+        0x5e, 0x1a, 0x4d, 0x22, 0x1c, 0x00, 0x15, 0x16, 0x05, 0x00, 0x02, 0x15, 0x16, 0x05, 0x00,
+        0x20, 0xa1, 0x07, 0x00,
+        // VS_COLORFORMAT
+        0x06, 0x24, 0x0d, 0x01, 0x01, 0x04,
+    ];
+
+    /// A Video Streaming interface with a single MJPEG format.
+    #[rustfmt::skip]
+    const MJPEG_STREAMING: &[u8] = &[
+        // VS_INPUT_HEADER
+        0x0e, 0x24, 0x01, 0x01, 0x5d, 0x00, 0x82, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00,
+        // VS_FORMAT_MJPEG
+        0x0b, 0x24, 0x06, 0x01, 0x02, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00,
+        // VS_FRAME_MJPEG (640x480, continuous intervals)
+        0x26, 0x24, 0x07, 0x01, 0x00, 0x80, 0x02, 0xe0, 0x01, 0x00, 0x00, 0x65, 0x04, 0x00, 0x00,
+        0xca, 0x08, 0x00, 0x60, 0x09, 0x00, 0x15, 0x16, 0x05, 0x00, 0x00, 0x15, 0x16, 0x05, 0x00,
+        0x40, 0x42, 0x0f, 0x00, 0x15, 0x16, 0x05, 0x00,
+        // VS_FRAME_MJPEG (1280x720)
+        0x1e, 0x24, 0x07, 0x02, 0x00, 0x00, 0x05, 0xd0, 0x02, 0x00, 0x00, 0x2f, 0x0d, 0x00, 0x00,
+        0x5e, 0x1a, 0x00, 0x20, 0x1c, 0x00, 0x15, 0x16, 0x05, 0x00, 0x01, 0x15, 0x16, 0x05, 0x00,
+    ];
+
+    /// Video Control descriptors with extension units whose declared sizes exceed their `bLength`.
+    #[rustfmt::skip]
+    const MALFORMED_EXTENSION_UNITS: &[u8] = &[
+        // VC_HEADER
+        0x0d, 0x24, 0x01, 0x00, 0x01, 0x4c, 0x00, 0x80, 0xc3, 0xc9, 0x01, 0x01, 0x01,
+        // VC_EXTENSION_UNIT with `bNrInPins` = 255
+        0x1b, 0x24, 0x06, 0x06, 0xd0, 0x9e, 0xe4, 0x23, 0x78, 0x11, 0x31, 0x4f, 0xae, 0x52, 0xd2,
+        0xfb, 0x8a, 0x8d, 0x3b, 0x48, 0x0a, 0xff, 0x03, 0x02, 0xff, 0x03, 0x00,
+        // VC_EXTENSION_UNIT with `bControlSize` = 64
+        0x1b, 0x24, 0x06, 0x07, 0xd0, 0x9e, 0xe4, 0x23, 0x78, 0x11, 0x31, 0x4f, 0xae, 0x52, 0xd2,
+        0xfb, 0x8a, 0x8d, 0x3b, 0x48, 0x0a, 0x01, 0x06, 0x40, 0xff, 0x03, 0x00,
+        // VC_OUTPUT_TERMINAL
+        0x09, 0x24, 0x03, 0x04, 0x01, 0x01, 0x00, 0x07, 0x00,
+    ];
+
+    fn parse_control(extra: &[u8]) -> (Result<Topology>, Vec<ParseWarning>) {
+        let warnings = ParseWarnings::default();
+        let res = parse_control_extra(extra, DEFAULT_REPAIR_PADDING, &warnings);
+        (res, warnings.into_vec())
+    }
+
+    #[test]
+    fn control_interface() {
+        let (topo, warnings) = parse_control(C920_CONTROL);
+        let topo = topo.unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        assert_eq!(topo.header().uvc_version().major(), 1);
+        assert_eq!(topo.header().uvc_version().minor(), 0);
+        assert_eq!(topo.header().clock_frequency(), 30_000_000);
+
+        assert_eq!(topo.inputs().len(), 1);
+        let camera = topo.inputs()[0].as_camera_desc().unwrap();
+        assert_eq!(camera.raw_controls_bitmap(), [0x2e, 0x0a, 0x02]);
+        assert!(camera.controls().contains(
+            CameraControls::AUTO_EXPOSURE_MODE
+                | CameraControls::EXPOSURE_TIME_ABS
+                | CameraControls::FOCUS_ABS
+                | CameraControls::ZOOM_ABS
+                | CameraControls::PAN_TILT_ABS
+                | CameraControls::FOCUS_AUTO
+        ));
+
+        assert_eq!(topo.units().len(), 2);
+        let pu = topo.units()[0].as_processing_unit().unwrap();
+        assert_eq!(pu.id().as_raw(), 3);
+        assert_eq!(pu.raw_controls_bitmap(), [0x5b, 0x17]);
+        assert!(pu.controls().contains(
+            ProcessingUnitControls::BRIGHTNESS
+                | ProcessingUnitControls::CONTRAST
+                | ProcessingUnitControls::SATURATION
+                | ProcessingUnitControls::SHARPNESS
+                | ProcessingUnitControls::WHITE_BALANCE_TEMPERATURE
+                | ProcessingUnitControls::GAIN
+                | ProcessingUnitControls::WHITE_BALANCE_TEMPERATURE_AUTO
+        ));
+
+        let xu = topo.units()[1].as_extension_unit().unwrap();
+        assert_eq!(xu.id().as_raw(), 6);
+        assert_eq!(
+            xu.extension_code().to_string(),
+            "23e49ed0-1178-4f31-ae52-d2fb8a8d3b48"
+        );
+        assert_eq!(xu.num_controls(), 10);
+        assert_eq!(xu.inputs(), [SourceId::new(3).unwrap()]);
+        assert_eq!(xu.raw_controls_bitmap(), [0xff, 0x03]);
+
+        assert_eq!(topo.outputs().len(), 1);
+        let output = &topo.outputs()[0];
+        assert_eq!(output.id(), TermId::new(4).unwrap());
+        assert_eq!(output.source(), SourceId::new(6).unwrap());
+        assert!(matches!(
+            output.terminal_type(),
+            Some(OutputTerminalType::UsbStreaming)
+        ));
+        assert!(topo.unknown_descriptors().is_empty());
+    }
+
+    #[test]
+    fn control_interface_without_header() {
+        // Drop the VC_HEADER descriptor.
+        let (topo, _) = parse_control(&C920_CONTROL[13..]);
+        assert!(topo.unwrap_err().is_descriptor_error());
+    }
+
+    #[test]
+    fn truncated_descriptor_location() {
+        // Truncate the VC_PROCESSING_UNIT to its first 4 Bytes, without repairing it.
+        let mut extra = C920_CONTROL[..31].to_vec();
+        extra.extend_from_slice(&[0x04, 0x24, 0x05, 0x03]);
+        let warnings = ParseWarnings::default();
+        let err = parse_control_extra(&extra, 0, &warnings).unwrap_err();
+
+        let loc = err.descriptor_location().unwrap();
+        assert_eq!(loc.descriptor_type(), 0x24);
+        assert_eq!(loc.descriptor_subtype(), Some(0x05));
+        assert_eq!(loc.offset(), 31);
+    }
+
+    #[test]
+    fn malformed_extension_units() {
+        let (topo, warnings) = parse_control(MALFORMED_EXTENSION_UNITS);
+        let topo = topo.unwrap();
+
+        let source = |id| SourceId::new(id).unwrap();
+        let units = topo.units();
+        assert_eq!(units.len(), 2);
+
+        // `bNrInPins` is clamped to the 3 Bytes left after it (minus `bControlSize` and
+        // `iExtension`), which leaves no room for the bitmap.
+        let xu = units[0].as_extension_unit().unwrap();
+        assert_eq!(xu.id().as_raw(), 6);
+        assert_eq!(xu.inputs(), [source(3), source(2), source(0xff)]);
+        assert!(xu.raw_controls_bitmap().is_empty());
+
+        // `bControlSize` is clamped to the 2 Bytes before `iExtension`.
+        let xu = units[1].as_extension_unit().unwrap();
+        assert_eq!(xu.id().as_raw(), 7);
+        assert_eq!(xu.inputs(), [source(6)]);
+        assert_eq!(xu.raw_controls_bitmap(), [0xff, 0x03]);
+
+        let messages = warnings.iter().map(|w| w.message()).collect::<Vec<_>>();
+        assert_eq!(messages.len(), 3, "{:?}", messages);
+        assert!(messages[0].contains("`bNrInPins` is 255"));
+        assert!(messages[1].contains("`bControlSize` is 3"));
+        assert!(messages[2].contains("`bControlSize` is 64"));
+
+        // The descriptor following the malformed ones is still parsed correctly.
+        assert_eq!(topo.outputs().len(), 1);
+        assert_eq!(topo.outputs()[0].source(), source(7));
+    }
+
+    #[test]
+    fn streaming_interface() {
+        let warnings = ParseWarnings::default();
+        let desc =
+            parse_streaming_extra(1, C920_STREAMING, DEFAULT_REPAIR_PADDING, &warnings).unwrap();
+        let warnings = warnings.into_vec();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        assert_eq!(desc.id().0, 1);
+        assert_eq!(desc.endpoint_address(), 0x81);
+        assert_eq!(desc.endpoint_direction(), EndpointDirection::In);
+        assert_eq!(desc.terminal_link(), TermId::new(4).unwrap());
+        assert!(desc.input_info().unwrap().is_empty());
+        assert!(desc.still_capture_method() == Some(StillCaptureMethod::None));
+        assert!(desc.alt_settings().is_empty());
+
+        assert_eq!(desc.formats().len(), 2);
+        let yuy2 = &desc.formats()[0];
+        assert_eq!(yuy2.index().0, 1);
+        assert_eq!(yuy2.format_name().as_deref(), Some("YUY2"));
+        assert!(matches!(
+            yuy2.as_format_uncompressed().unwrap().pixel_format(),
+            Some(PixelFormat::Yuy2)
+        ));
+        let color = yuy2.color_matching().unwrap();
+        assert_eq!(color.color_primaries(), ColorPrimaries::Bt709);
+        assert_eq!(
+            color.transfer_characteristics(),
+            TransferCharacteristics::Bt709
+        );
+        assert_eq!(color.matrix_coefficients(), MatrixCoefficients::Smpte170M);
+        let format = desc.format_by_pixel_format(PixelFormat::Yuy2).unwrap();
+        assert_eq!(format.index().0, 1);
+        assert!(desc.format_by_pixel_format(PixelFormat::Nv12).is_none());
+
+        let mjpeg = &desc.formats()[1];
+        assert_eq!(mjpeg.index().0, 2);
+        assert!(mjpeg.as_format_mjpeg().is_some());
+        assert!(mjpeg.color_matching().is_some());
+
+        assert_eq!(desc.frames().len(), 3);
+        let frame = desc
+            .try_frame_by_index(FormatIndex(1), FrameIndex(1))
+            .unwrap();
+        assert_eq!(frame.resolution(), (640, 480));
+        assert_eq!(
+            frame.default_frame_interval(),
+            Duration::from_nanos(33_333_300)
+        );
+        match frame.frame_intervals() {
+            SupportedFrameIntervals::Discrete {
+                supported_frame_intervals,
+            } => assert_eq!(
+                supported_frame_intervals,
+                &[
+                    Duration::from_nanos(33_333_300),
+                    Duration::from_nanos(66_666_600)
+                ]
+            ),
+            other => panic!("unexpected frame intervals {:?}", other),
+        }
+        let frame = desc
+            .try_frame_by_index(FormatIndex(2), FrameIndex(2))
+            .unwrap();
+        assert_eq!(frame.resolution(), (1280, 720));
+        assert!(desc
+            .try_frame_by_index(FormatIndex(1), FrameIndex(2))
+            .is_none());
+
+        assert_eq!(
+            desc.supported_resolutions(),
+            [(640, 480), (1280, 720), (1920, 1080)]
+        );
+        assert!(desc.still_image_frames().is_empty());
+        assert!(desc.unknown_descriptors().is_empty());
+    }
+
+    #[test]
+    fn mjpeg_format() {
+        let desc = StreamingInterfaceDesc::parse_from_bytes(2, MJPEG_STREAMING).unwrap();
+        assert_eq!(desc.endpoint_address(), 0x82);
+
+        assert_eq!(desc.formats().len(), 1);
+        let format = &desc.formats()[0];
+        assert_eq!(format.index().0, 1);
+        assert!(format.as_format_uncompressed().is_none());
+        let mjpeg = format.as_format_mjpeg().unwrap();
+        assert_eq!(mjpeg.default_frame_index().0, 2);
+        assert!(mjpeg.flags().contains(MjpegFormatFlags::FIXED_SIZE_SAMPLES));
+
+        assert_eq!(desc.frames().len(), 2);
+        let frame = desc
+            .try_frame_by_index(FormatIndex(1), FrameIndex(1))
+            .unwrap();
+        let mjpeg = frame.as_frame_mjpeg().unwrap();
+        assert_eq!((mjpeg.width(), mjpeg.height()), (640, 480));
+        assert_eq!(mjpeg.max_video_frame_buffer_size(), 614_400);
+        assert!(matches!(
+            mjpeg.frame_intervals(),
+            SupportedFrameIntervals::Continuous { .. }
+        ));
+
+        let frame = desc
+            .try_frame_by_index(FormatIndex(1), FrameIndex(2))
+            .unwrap();
+        assert_eq!(frame.resolution(), (1280, 720));
+        assert_eq!(
+            frame.default_frame_interval(),
+            Duration::from_nanos(33_333_300)
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    convert::TryFrom,
     fmt,
     io::{self, Read},
     time::Duration,
@@ -7,11 +8,12 @@ use std::{
 use byteorder::{ReadBytesExt, LE};
 use uuid::Uuid;
 
-use crate::topo::{SourceId, TermId, UnitId};
+use crate::topo::{parse::ParseWarnings, SourceId, TermId, UnitId};
 
 /// primitive_enum! {}
 macro_rules! primitive_enum {
     (
+        $( #[$attrs:meta] )*
         $v:vis enum $name:ident: $native:ty {
             $(
                 $( #[$variant_attrs:meta] )*
@@ -20,15 +22,19 @@ macro_rules! primitive_enum {
             $(,)?
         }
     ) => {
+        $( #[$attrs] )*
         #[derive(Clone, Copy, PartialEq, Eq)]
         $v enum $name {
             $(
+                $( #[$variant_attrs] )*
                 $variant = $value,
             )+
         }
 
         impl $name {
-            pub(crate) fn from_raw(raw: $native) -> Option<Self> {
+            /// Converts a raw value to the corresponding variant, returning `None` if the value
+            /// is invalid.
+            pub fn from_raw(raw: $native) -> Option<Self> {
                 match raw {
                     $(
                         $value => Some(Self::$variant),
@@ -51,16 +57,19 @@ macro_rules! primitive_enum {
     };
 }
 
-pub(crate) fn split_descriptors(mut raw: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+pub(crate) fn split_descriptors<'a>(
+    mut raw: &'a [u8],
+    warnings: &'a ParseWarnings,
+) -> impl Iterator<Item = (u8, &'a [u8])> {
     std::iter::from_fn(move || match raw {
         [length, descriptor_type, ..] => {
             let length = *length as usize;
             if length > raw.len() {
-                log::warn!(
+                warnings.warn(format!(
                     "descriptor length {} exceeds available data ({} bytes)",
                     length,
                     raw.len()
-                );
+                ));
                 return None;
             }
             let (desc_data, next) = raw.split_at(length);
@@ -71,15 +80,16 @@ pub(crate) fn split_descriptors(mut raw: &[u8]) -> impl Iterator<Item = (u8, &[u
         }
         [] => None,
         _ => {
-            log::warn!("invalid trailing descriptor bytes: {:x?}", raw);
+            warnings.warn(format!("invalid trailing descriptor bytes: {:x?}", raw));
             None
         }
     })
 }
 
 pub(crate) trait BytesExt {
-    fn read_length_prefixed_bitmask(&mut self) -> io::Result<u32>;
+    fn read_length_prefixed_bitmask_vec(&mut self) -> io::Result<Vec<u8>>;
     fn read_bitmask(&mut self, len: u8) -> io::Result<u32>;
+    fn read_bitmask_vec(&mut self, len: u8) -> io::Result<Vec<u8>>;
     fn read_nonzero_source_id(&mut self) -> io::Result<SourceId>;
     fn read_nonzero_term_id(&mut self) -> io::Result<TermId>;
     fn read_nonzero_unit_id(&mut self) -> io::Result<UnitId>;
@@ -88,28 +98,28 @@ pub(crate) trait BytesExt {
 }
 
 impl BytesExt for &'_ [u8] {
-    fn read_length_prefixed_bitmask(&mut self) -> io::Result<u32> {
+    fn read_length_prefixed_bitmask_vec(&mut self) -> io::Result<Vec<u8>> {
         let len = self.read_u8()?;
-        self.read_bitmask(len)
+        self.read_bitmask_vec(len)
     }
 
     fn read_bitmask(&mut self, len: u8) -> io::Result<u32> {
+        if len > 4 {
+            log::warn!("bitmask length {}, discarding data past 32 bits", len);
+        }
+
+        Ok(truncate_bitmask(&self.read_bitmask_vec(len)?))
+    }
+
+    fn read_bitmask_vec(&mut self, len: u8) -> io::Result<Vec<u8>> {
         let len = usize::from(len);
         if len > self.len() {
             return Err(io::ErrorKind::UnexpectedEof.into());
         }
-        if len > 4 {
-            log::warn!("bitmask length {}, discarding data past 32 bits", len);
-        }
 
         let (bytes, rest) = self.split_at(len);
         *self = rest;
-
-        let mut buf = [0u8; 4];
-        buf.iter_mut()
-            .zip(bytes)
-            .for_each(|(dest, src)| *dest = *src);
-        Ok(u32::from_le_bytes(buf))
+        Ok(bytes.to_vec())
     }
 
     fn read_nonzero_source_id(&mut self) -> io::Result<SourceId> {
@@ -139,11 +149,33 @@ impl BytesExt for &'_ [u8] {
     }
 
     fn read_time_100ns(&mut self) -> io::Result<Duration> {
-        let units = self.read_u32::<LE>()?;
-        Ok(Duration::from_nanos(u64::from(units) * 100))
+        Ok(duration_from_100ns(self.read_u32::<LE>()?))
     }
 }
 
+/// Returns the first 32 bits of a little-endian bitmap, zero-extending shorter bitmaps.
+pub(crate) fn truncate_bitmask(bytes: &[u8]) -> u32 {
+    let mut buf = [0u8; 4];
+    buf.iter_mut()
+        .zip(bytes)
+        .for_each(|(dest, src)| *dest = *src);
+    u32::from_le_bytes(buf)
+}
+
+/// Converts a time in the 100 ns units used throughout UVC to a [`Duration`].
+pub(crate) fn duration_from_100ns(units: u32) -> Duration {
+    Duration::from_nanos(u64::from(units) * 100)
+}
+
+/// Converts a [`Duration`] to 100 ns units, rounding to the nearest unit.
+///
+/// Durations that don't fit in a `u32` saturate to `u32::MAX`. This is the inverse of
+/// [`duration_from_100ns`].
+pub(crate) fn duration_to_100ns(dur: Duration) -> u32 {
+    let units = (dur.as_nanos() + 50) / 100;
+    u32::try_from(units).unwrap_or(u32::MAX)
+}
+
 pub(crate) fn io_err_res<T, M>(msg: M) -> io::Result<T>
 where
     M: Into<Box<dyn std::error::Error + Send + Sync>>,
@@ -158,9 +190,58 @@ where
     io::Error::new(io::ErrorKind::Other, msg)
 }
 
-#[derive(Clone, Copy)]
+/// Displays a format GUID as its FourCC code (eg. `YUY2`), or as the full GUID if its first 4
+/// Bytes aren't printable ASCII.
+pub(crate) struct FormatGuid<'a>(pub(crate) &'a Uuid);
+
+impl FormatGuid<'_> {
+    /// Returns the FourCC code stored in the first 4 Bytes of the GUID, if it is printable.
+    pub(crate) fn fourcc(&self) -> Option<[u8; 4]> {
+        // `read_guid` decoded the first field as little endian, which is the FourCC byte order.
+        let bytes = self.0.as_fields().0.to_le_bytes();
+        if bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+            Some(bytes)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for FormatGuid<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.fourcc() {
+            // unwrap: the bytes were checked to be ASCII
+            Some(fourcc) => f.write_str(std::str::from_utf8(&fourcc).unwrap().trim_end()),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl fmt::Debug for FormatGuid<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A version number in binary-coded decimal, as used by USB descriptors.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BcdVersion(pub(crate) u16);
 
+impl BcdVersion {
+    pub(crate) const UVC_1_1: Self = Self(0x0110);
+    pub(crate) const UVC_1_5: Self = Self(0x0150);
+
+    /// Returns the major version number.
+    pub fn major(self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    /// Returns the minor version number (eg. `0x10` for version 1.10).
+    pub fn minor(self) -> u8 {
+        self.0 as u8
+    }
+}
+
 impl fmt::Display for BcdVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let maj = self.0 >> 8;